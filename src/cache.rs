@@ -0,0 +1,122 @@
+use crate::generator::RandomUserError;
+use crate::transport::{build_response, UserTransport};
+use crate::types::api_error;
+use bytes::Bytes;
+use futures_util::future::BoxFuture;
+use reqwest::header::{HeaderMap, CONTENT_TYPE, ETAG, IF_NONE_MATCH};
+use reqwest::StatusCode;
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex, PoisonError};
+
+/// Successful response kept to answer the same request again
+#[derive(Clone)]
+struct CachedResponse {
+    status: StatusCode,
+    headers: HeaderMap,
+    body: Bytes,
+}
+
+impl CachedResponse {
+    /// Whether the api answered with an error despite the successful status,
+    /// as plain text or as a json body with an `error` key
+    fn is_api_error(&self) -> bool {
+        let plain_text = self
+            .headers
+            .get(CONTENT_TYPE)
+            .and_then(|content_type| content_type.to_str().ok())
+            .is_some_and(|content_type| content_type.contains("text/plain"));
+        plain_text
+            || serde_json::from_slice(&self.body)
+                .ok()
+                .and_then(|body| api_error(&body))
+                .is_some()
+    }
+
+    fn to_response(&self) -> reqwest::Response {
+        build_response(self.status, self.headers.clone(), self.body.clone())
+    }
+}
+
+/// Transport keeping the responses of seeded requests, keyed by their url,
+/// dropping the least recently used one when full
+///
+/// When revalidating, responses with an `ETag` are requested again with `If-None-Match`,
+/// and kept if the api answers `304 Not Modified`.
+pub(crate) struct CachedTransport {
+    inner: Arc<dyn UserTransport>,
+    capacity: usize,
+    revalidate: bool,
+    entries: Mutex<VecDeque<(String, CachedResponse)>>,
+}
+
+impl CachedTransport {
+    pub(crate) fn new(inner: Arc<dyn UserTransport>, capacity: usize, revalidate: bool) -> Self {
+        Self {
+            inner,
+            capacity,
+            revalidate,
+            entries: Mutex::new(VecDeque::new()),
+        }
+    }
+
+    fn get(&self, key: &str) -> Option<CachedResponse> {
+        let mut entries = self.entries.lock().unwrap_or_else(PoisonError::into_inner);
+        let index = entries.iter().position(|(k, _)| k == key)?;
+        let entry = entries.remove(index)?;
+        let response = entry.1.clone();
+        entries.push_back(entry);
+        Some(response)
+    }
+
+    fn insert(&self, key: String, response: CachedResponse) {
+        if self.capacity == 0 {
+            return;
+        }
+        let mut entries = self.entries.lock().unwrap_or_else(PoisonError::into_inner);
+        entries.retain(|(k, _)| *k != key);
+        if entries.len() == self.capacity {
+            entries.pop_front();
+        }
+        entries.push_back((key, response));
+    }
+}
+
+impl UserTransport for CachedTransport {
+    fn send(
+        &self,
+        mut request: reqwest::Request,
+    ) -> BoxFuture<'_, Result<reqwest::Response, RandomUserError>> {
+        Box::pin(async move {
+            // Unseeded requests return new users every time
+            if !request.url().query_pairs().any(|(key, _)| key == "seed") {
+                return self.inner.send(request).await;
+            }
+            let key = request.url().to_string();
+            let cached = self.get(&key);
+            if let Some(cached) = &cached {
+                match cached.headers.get(ETAG) {
+                    Some(etag) if self.revalidate => {
+                        request.headers_mut().insert(IF_NONE_MATCH, etag.clone());
+                    }
+                    _ => return Ok(cached.to_response()),
+                }
+            }
+            let response = self.inner.send(request).await?;
+            if let (Some(cached), StatusCode::NOT_MODIFIED) = (&cached, response.status()) {
+                return Ok(cached.to_response());
+            }
+            if !response.status().is_success() {
+                return Ok(response);
+            }
+            let cached = CachedResponse {
+                status: response.status(),
+                headers: response.headers().clone(),
+                body: response.bytes().await?,
+            };
+            if !cached.is_api_error() {
+                self.insert(key, cached.clone());
+            }
+            Ok(cached.to_response())
+        })
+    }
+}
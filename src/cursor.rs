@@ -0,0 +1,75 @@
+use crate::generator::{RandomUserError, UserGeneratorBuilder};
+use crate::types::RandomUser;
+
+/// Position in a seeded dataset, fetching one page after the other
+///
+/// Created with [`UserGenerator::cursor`](crate::UserGenerator::cursor)
+/// or [`UserGeneratorBuilder::cursor`] to apply filters.
+///
+/// ## Example:
+/// ```no_run
+/// #[tokio::main]
+/// async fn main() {
+///     use random_user::UserGenerator;
+///
+///     let generator = UserGenerator::new();
+///
+///     let mut cursor = generator.cursor("foobar", 100);
+///     while let Some(users) = cursor.next_page().await.unwrap() {
+///         println!("Page {}: {} users", cursor.page() - 1, users.len());
+///         if cursor.page() > 5 {
+///             break;
+///         }
+///     }
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct Cursor {
+    builder: UserGeneratorBuilder,
+    seed: String,
+    page_size: usize,
+    page: u32,
+    done: bool,
+}
+
+impl Cursor {
+    pub(crate) fn new(builder: UserGeneratorBuilder, seed: &str, page_size: usize) -> Self {
+        Self {
+            builder,
+            seed: seed.to_owned(),
+            page_size,
+            page: 1,
+            done: false,
+        }
+    }
+
+    /// Fetch the next page, `None` once a page returned fewer users than requested
+    ///
+    /// The users not matching the [filters](UserGeneratorBuilder::filter) are dropped from the page.
+    /// On error, the same page is requested again by the next call.
+    pub async fn next_page(&mut self) -> Result<Option<Vec<RandomUser>>, RandomUserError> {
+        if self.done {
+            return Ok(None);
+        }
+        let mut users = self
+            .builder
+            .clone()
+            .seed(&self.seed)
+            .page(self.page)
+            .fetch_unfiltered(self.page_size)
+            .await?;
+        self.done = users.len() < self.page_size;
+        self.page += 1;
+        users.retain(|user| self.builder.matches_filters(user));
+        Ok(Some(users))
+    }
+
+    /// Number of the page the next call to [`next_page`](Self::next_page) fetches
+    pub fn page(&self) -> u32 {
+        self.page
+    }
+
+    pub fn seed(&self) -> &str {
+        &self.seed
+    }
+}
@@ -1,180 +1,2346 @@
+use crate::cache::CachedTransport;
+use crate::cursor::Cursor;
+#[cfg(feature = "offline")]
+use crate::offline::Fallback;
+use crate::password::{PasswordSpec, PasswordSpecError};
+use crate::rate_limit::RateLimitedTransport;
+use crate::retry::RetryPolicy;
+use crate::transport::{HttpTransport, MockTransport, UserTransport};
 use crate::types::*;
-use enumset::EnumSet;
+use chrono::{DateTime, Utc};
+use futures_util::future;
+use futures_util::stream::{self, Stream, StreamExt, TryStreamExt};
+use rand::distributions::Alphanumeric;
+use rand::seq::SliceRandom;
+use rand::Rng;
+use reqwest::header::{HeaderValue, RETRY_AFTER, USER_AGENT};
+use reqwest::StatusCode;
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::future::Future;
+use std::io::Write;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use std::time::{Duration, Instant};
 use thiserror::Error;
+use url::Url;
 /// Helper to request users with filters like gender, nationalities, etc.
+///
+/// The builder can be cloned to reuse the same filters for several requests
+///
+/// ## Example:
+/// ```no_run
+/// #[tokio::main]
+/// async fn main() {
+///     use random_user::{Nationality, UserGenerator};
+///
+///     let generator = UserGenerator::new();
+///
+///     let template = generator
+///         .get()
+///         .nationality(Nationality::French)
+///         .password("upper,lower,12");
+///
+///     let users = template.clone().fetch(5).await.unwrap();
+///     let other_users = template.fetch(10).await.unwrap();
+/// }
+/// ```
+#[derive(Debug, Clone)]
 pub struct UserGeneratorBuilder {
-    req: reqwest::RequestBuilder,
-    gender: Option<EnumSet<Gender>>,
+    client: reqwest::Client,
+    transport: Arc<dyn UserTransport>,
+    url: Url,
+    user_agent: Option<HeaderValue>,
+    timeout: Option<Duration>,
+    retry: RetryPolicy,
+    gender: Option<Gender>,
+    nationalities: Vec<Nationality>,
+    include: Vec<Field>,
+    exclude: Vec<Field>,
+    seed: Option<String>,
+    no_info: bool,
+    query: Vec<(String, String)>,
+    filters: Vec<UserFilter>,
+    #[cfg(feature = "offline")]
+    offline_seed: Option<String>,
 }
 
-impl UserGeneratorBuilder {
-    pub(crate) fn new(req: reqwest::RequestBuilder, gender: Option<EnumSet<Gender>>) -> Self {
-        Self { req, gender }
+impl UserGeneratorBuilder {
+    /// Maximum number of users the api returns in a single request,
+    /// fetching more users at once requires [`fetch_many`](Self::fetch_many)
+    pub const MAX_RESULTS: usize = 5000;
+
+    /// Maximum number of requests sent to collect enough users matching a filter applied locally
+    const FILTER_ATTEMPTS: u32 = 10;
+
+    /// Multiplier of the missing users requested when fetching with [`filter`](Self::filter)
+    const FILTER_OVER_FETCH: usize = 2;
+
+    /// Approximate number of distinct ages generated by the api, from about 20 to 80
+    const AGE_SPAN: usize = 60;
+
+    pub(crate) fn new(generator: &UserGenerator) -> Self {
+        let builder = Self {
+            client: generator.client.clone(),
+            transport: generator.transport.clone(),
+            url: generator.base_url.clone(),
+            user_agent: generator.user_agent.clone(),
+            timeout: generator.timeout,
+            retry: generator.retry,
+            gender: None,
+            nationalities: generator.nationalities.clone(),
+            include: Vec::new(),
+            exclude: Vec::new(),
+            seed: None,
+            no_info: false,
+            query: Vec::new(),
+            filters: Vec::new(),
+            #[cfg(feature = "offline")]
+            offline_seed: generator.offline_seed.clone(),
+        };
+        match &generator.password {
+            Some(charset) => builder.password(charset),
+            None => builder,
+        }
+    }
+
+    /// Request a specific gender
+    ///
+    /// The api can only filter a single gender, calling this again replaces the previous one.
+    /// Without this filter, users of any gender are returned.
+    ///
+    /// ## Example:
+    /// ```no_run
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     use random_user::{Gender, UserGenerator};
+    ///
+    ///     let generator = UserGenerator::new();
+    ///
+    ///     // Only women
+    ///     let women = generator.get().gender(Gender::Female).fetch(5).await.unwrap();
+    /// }
+    /// ```
+    pub fn gender(self, gender: Gender) -> Self {
+        Self {
+            gender: Some(gender),
+            ..self
+        }
+    }
+
+    /// Request users of the same nationality and gender as `user`,
+    /// replacing the previous nationality and gender filters
+    ///
+    /// If the api can't filter the gender of `user`, users of any gender are returned.
+    ///
+    /// ## Example:
+    /// ```no_run
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     use random_user::UserGenerator;
+    ///
+    ///     let generator = UserGenerator::new();
+    ///
+    ///     let user = generator.fetch_one().await.unwrap();
+    ///     let similar = generator.get().like(&user).fetch(5).await.unwrap();
+    ///     assert!(similar.iter().all(|other| other.nationality == user.nationality));
+    /// }
+    /// ```
+    pub fn like(self, user: &RandomUser) -> Self {
+        let gender = match user.gender {
+            Gender::Other(_) => None,
+            ref gender => Some(gender.clone()),
+        };
+        Self {
+            gender,
+            ..self.nationality(user.nationality)
+        }
+    }
+
+    /// Request a specific nationality, replacing any previously requested
+    pub fn nationality(self, nationality: Nationality) -> Self {
+        Self {
+            nationalities: vec![nationality],
+            ..self
+        }
+    }
+
+    /// Request specific nationalities, picked at random between each user,
+    /// replacing any previously requested
+    ///
+    /// An empty slice removes the filter, so users of any nationality are returned.
+    ///
+    /// ## Example:
+    /// ```
+    /// use random_user::{Nationality, UserGenerator};
+    ///
+    /// let generator = UserGenerator::new().with_default_nationality(Nationality::Irish);
+    ///
+    /// let url = generator.get().nationalities(&[]).build_url().unwrap();
+    /// assert!(url.query_pairs().all(|(key, _)| key != "nat"));
+    /// ```
+    pub fn nationalities(self, nationalities: &[Nationality]) -> Self {
+        Self {
+            nationalities: nationalities.to_vec(),
+            ..self
+        }
+    }
+
+    /// Add a nationality to the ones already requested
+    ///
+    /// ## Example:
+    /// ```no_run
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     use random_user::{Nationality, UserGenerator};
+    ///
+    ///     let generator = UserGenerator::new();
+    ///
+    ///     // Sends `nat=FR,GB` once
+    ///     let users = generator
+    ///         .get()
+    ///         .nationality(Nationality::French)
+    ///         .add_nationality(Nationality::British)
+    ///         .fetch(5)
+    ///         .await
+    ///         .unwrap();
+    /// }
+    /// ```
+    pub fn add_nationality(mut self, nationality: Nationality) -> Self {
+        if !self.nationalities.contains(&nationality) {
+            self.nationalities.push(nationality);
+        }
+        self
+    }
+
+    /// Request with a specified seed, allow to always generate the same users
+    ///
+    /// The seed is url-encoded, so it can contain any character.
+    /// An empty seed is not sent, the api then picks a random one.
+    ///
+    /// ### Warning:
+    /// May discard other filters
+    ///
+    /// ## Example:
+    /// ```
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     use futures_util::future::BoxFuture;
+    ///     use random_user::{RandomUserError, UserGenerator, UserTransport};
+    ///
+    ///     // Fail with the seed received by the api
+    ///     struct EchoSeed;
+    ///
+    ///     impl UserTransport for EchoSeed {
+    ///         fn send(
+    ///             &self,
+    ///             request: reqwest::Request,
+    ///         ) -> BoxFuture<'_, Result<reqwest::Response, RandomUserError>> {
+    ///             let seed = request
+    ///                 .url()
+    ///                 .query_pairs()
+    ///                 .find(|(key, _)| key == "seed")
+    ///                 .map(|(_, seed)| seed.into_owned());
+    ///             Box::pin(async move { Err(RandomUserError::Api(seed.unwrap_or_default())) })
+    ///         }
+    ///     }
+    ///
+    ///     let generator = UserGenerator::new().with_transport(EchoSeed);
+    ///     let res = generator.get().seed("foo bar&baz=1").fetch(1).await;
+    ///     assert!(matches!(res, Err(RandomUserError::Api(seed)) if seed == "foo bar&baz=1"));
+    /// }
+    /// ```
+    pub fn seed(self, seed: &str) -> Self {
+        Self {
+            seed: Some(seed.to_owned()).filter(|seed| !seed.is_empty()),
+            ..self
+        }
+    }
+
+    /// Use a numeric seed, like an id, see [`seed`](Self::seed)
+    ///
+    /// The api treats seeds as opaque strings, so this is the same as the decimal seed.
+    ///
+    /// ## Example:
+    /// ```
+    /// use random_user::UserGenerator;
+    ///
+    /// let generator = UserGenerator::new();
+    ///
+    /// assert_eq!(
+    ///     generator.get().seed_u64(42).build_url().unwrap(),
+    ///     generator.get().seed("42").build_url().unwrap()
+    /// );
+    /// ```
+    pub fn seed_u64(self, seed: u64) -> Self {
+        self.seed(&seed.to_string())
+    }
+
+    /// Request a specific page of results
+    ///
+    /// Paging is only meaningful along with a [`seed`](Self::seed):
+    /// with the same seed and count, each page returns distinct users,
+    /// and the same page always returns the same users.
+    /// Without a seed, every page is a new random batch.
+    ///
+    /// ## Example:
+    /// ```no_run
+    /// // Get the first 3 pages of 10 users from the same dataset
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     use random_user::UserGenerator;
+    ///
+    ///     let generator = UserGenerator::new();
+    ///
+    ///     for page in 1..=3 {
+    ///         let users = generator.get().seed("foobar").page(page).fetch(10).await.unwrap();
+    ///     }
+    /// }
+    /// ```
+    pub fn page(self, page: u32) -> Self {
+        self.query("page", page)
+    }
+
+    /// Add any query parameter, for api options not supported by this crate yet
+    ///
+    /// ### Warning:
+    /// The parameter is sent as is, along with the ones set by other methods,
+    /// an unexpected one can make the api return an error or users that fail to parse.
+    ///
+    /// ## Example:
+    /// ```no_run
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     use random_user::UserGenerator;
+    ///
+    ///     let generator = UserGenerator::new();
+    ///
+    ///     let users = generator.get().raw_param("lego", "").fetch(5).await.unwrap();
+    /// }
+    /// ```
+    pub fn raw_param(self, key: &str, value: &str) -> Self {
+        self.query(key, value)
+    }
+
+    /// Keep only the users matching a predicate, checked locally since the api can't filter them
+    ///
+    /// [`fetch`](Self::fetch) and the methods built on it request more users until enough
+    /// of them match, and return [`RandomUserError::NotEnoughUsers`] after 10 requests.
+    /// Seeded requests return the same users every time, so they can't be topped up
+    /// and fail after the first one. [`fetch_one_with_seed`](Self::fetch_one_with_seed)
+    /// likewise requests another user until one matches.
+    ///
+    /// The methods walking the pages of a seeded dataset ([`fetch_many`](Self::fetch_many),
+    /// [`fetch_with_progress`](Self::fetch_with_progress), [`fetch_lenient`](Self::fetch_lenient),
+    /// [`stream`](Self::stream), [`pages`](Self::pages), [`prefetched`](Self::prefetched) and [`Cursor`])
+    /// and the ones returning the api informations ([`fetch_with_info`](Self::fetch_with_info),
+    /// [`fetch_with_raw`](Self::fetch_with_raw) and [`fetch_timed`](Self::fetch_timed))
+    /// drop the users not matching instead, returning fewer users than requested.
+    /// The json returned by `fetch_with_raw` is left untouched, and the methods not returning
+    /// full users, like [`fetch_csv`](Self::fetch_csv) or [`fetch_partial`](Self::fetch_partial), ignore the filters.
+    ///
+    /// Calling this again adds another predicate, users must match all of them.
+    ///
+    /// ## Example:
+    /// ```no_run
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     use random_user::{Postcode, UserGenerator};
+    ///
+    ///     let generator = UserGenerator::new();
+    ///
+    ///     let users = generator
+    ///         .get()
+    ///         .filter(|user| matches!(user.location.postcode, Postcode::Alpha(_)))
+    ///         .fetch(10)
+    ///         .await
+    ///         .unwrap();
+    /// }
+    /// ```
+    pub fn filter(
+        mut self,
+        predicate: impl Fn(&RandomUser) -> bool + Send + Sync + 'static,
+    ) -> Self {
+        self.filters.push(UserFilter(Arc::new(predicate)));
+        self
+    }
+
+    /// Ask the api to omit its informations, making responses smaller
+    ///
+    /// [`fetch`](Self::fetch) and the other methods discarding the informations already do it,
+    /// the ones returning them ignore this option.
+    /// It is mostly useful for [`fetch_raw`](Self::fetch_raw).
+    pub fn no_info(self) -> Self {
+        Self {
+            no_info: true,
+            ..self
+        }
+    }
+
+    /// Request only some fields of the users, adding to the ones already included
+    ///
+    /// The other fields are missing from the response,
+    /// so the users must be fetched with [`fetch_partial`](Self::fetch_partial)
+    ///
+    /// ## Example:
+    /// ```no_run
+    /// // Get the name and email of 5 users
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     use random_user::{Field, UserGenerator};
+    ///
+    ///     let generator = UserGenerator::new();
+    ///
+    ///     let users = generator
+    ///         .get()
+    ///         .include(&[Field::Name, Field::Email])
+    ///         .fetch_partial(5)
+    ///         .await
+    ///         .unwrap();
+    ///
+    ///     for user in users {
+    ///         println!("{:?} {:?}", user.name, user.email);
+    ///     }
+    /// }
+    /// ```
+    pub fn include(mut self, fields: &[Field]) -> Self {
+        Field::extend(&mut self.include, fields);
+        self
+    }
+
+    /// Request all the fields of the users except some of them, adding to the ones already excluded
+    ///
+    /// The excluded fields are missing from the response,
+    /// so the users must be fetched with [`fetch_partial`](Self::fetch_partial)
+    ///
+    /// ## Example:
+    /// ```no_run
+    /// // Get 5 users without their login and picture
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     use random_user::{Field, UserGenerator};
+    ///
+    ///     let generator = UserGenerator::new();
+    ///
+    ///     let users = generator
+    ///         .get()
+    ///         .exclude(&[Field::Login, Field::Picture])
+    ///         .fetch_partial(5)
+    ///         .await
+    ///         .unwrap();
+    /// }
+    /// ```
+    pub fn exclude(mut self, fields: &[Field]) -> Self {
+        Field::extend(&mut self.exclude, fields);
+        self
+    }
+
+    /// Exclude the login of the users, so that no password, salt or hash is ever fetched
+    ///
+    /// The users must then be fetched with [`fetch_lite`](Self::fetch_lite),
+    /// or [`fetch_partial`](Self::fetch_partial) along with other exclusions.
+    pub fn without_credentials(self) -> Self {
+        self.exclude(&[Field::Login])
+    }
+
+    /// Request a user with specific password rules
+    ///
+    /// Format, without spaces:
+    /// `CHARSETS,MIN_LENGTH-MAX_LENGTH`
+    /// or
+    /// `CHARSETS,MAX_LENGTH`
+    ///
+    /// You can mix and match the charsets below for the CHARSETS option above:
+    /// ```md
+    /// special    !"#$%&'()*+,- ./:;<=>?@[\]^_`{|}~
+    /// upper      ABCDEFGHIJKLMNOPQRSTUVWXYZ
+    /// lower      abcdefghijklmnopqrstuvwxyz
+    /// number     0123456789
+    /// ```
+    /// `MIN_LENGTH` and `MAX_LENGTH` are the min/max length of the passwords that you want to generate.
+    /// By default, passwords will be between 8 - 64 characters long.
+    ///
+    /// Calling this again replaces the previous rules.
+    ///
+    /// ## Example:
+    /// ```no_run
+    /// // Get a user with a password composed with 8 upper and/or lower characters
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     use random_user::UserGenerator;
+    ///     
+    ///     let generator = UserGenerator::new();
+    ///
+    ///     let user = generator.get().password("upper,lower,8").fetch_one().await.unwrap();
+    /// }
+    /// ```
+    pub fn password(mut self, charset: &str) -> Self {
+        self.query.retain(|(key, _)| key != "password");
+        self.query("password", charset)
+    }
+
+    /// Request a user with specific password rules, checking them before sending the request
+    ///
+    /// Returns an error on unknown charsets or invalid lengths,
+    /// see [`PasswordSpec`] for the format of the rules.
+    ///
+    /// ## Example:
+    /// ```
+    /// use random_user::UserGenerator;
+    ///
+    /// let generator = UserGenerator::new();
+    ///
+    /// assert!(generator.get().try_password("upper,lower,10-50").is_ok());
+    /// assert!(generator.get().try_password("upper,lower,50-10").is_err());
+    /// assert!(generator.get().try_password("uper,lower,8").is_err());
+    /// ```
+    pub fn try_password(self, charset: &str) -> Result<Self> {
+        charset.parse::<PasswordSpec>()?;
+        Ok(self.password(charset))
+    }
+
+    /// Request passwords with at least `bits` of entropy, see [`PasswordSpec::with_min_entropy`]
+    ///
+    /// Returns an error if `bits` is negative, not finite, or too high for a 128 characters password.
+    ///
+    /// ## Example:
+    /// ```no_run
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     use random_user::UserGenerator;
+    ///
+    ///     let generator = UserGenerator::new();
+    ///
+    ///     let user = generator.get().min_password_entropy(80.0).unwrap().fetch_one().await.unwrap();
+    ///     assert!(user.login.password.len() >= 13);
+    /// }
+    /// ```
+    pub fn min_password_entropy(self, bits: f64) -> Result<Self> {
+        let spec = PasswordSpec::with_min_entropy(bits)?;
+        Ok(self.password(&spec.to_string()))
+    }
+
+    /// Generate users with the api informations
+    pub async fn fetch_with_info(self, count: usize) -> Result<RandomUserResult> {
+        let filters = self.filters.clone();
+        let mut res = self.fetch_info_unfiltered(count).await?;
+        res.results.retain(|user| UserFilter::all(&filters, user));
+        Ok(res)
+    }
+
+    async fn fetch_info_unfiltered(self, count: usize) -> Result<RandomUserResult> {
+        #[cfg(feature = "offline")]
+        let fallback = self.fallback();
+        let res = self.count(count)?.with_info().send().await;
+        #[cfg(feature = "offline")]
+        if let (Err(e), Some(fallback)) = (&res, fallback) {
+            if Fallback::applies(e) {
+                return Ok(fallback.result(count));
+            }
+        }
+        res
+    }
+
+    /// Generate users with the api informations, along with the json returned by the api
+    ///
+    /// Useful to debug deserialization, or to read fields unknown to this crate
+    ///
+    /// ## Example:
+    /// ```no_run
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     use random_user::UserGenerator;
+    ///
+    ///     let generator = UserGenerator::new();
+    ///
+    ///     let (users, json) = generator.get().fetch_with_raw(5).await.unwrap();
+    ///     println!("{json}");
+    /// }
+    /// ```
+    pub async fn fetch_with_raw(self, count: usize) -> Result<(RandomUserResult, String)> {
+        let filters = self.filters.clone();
+        let raw = self
+            .count(count)?
+            .with_info()
+            .send_with(Self::parse_raw)
+            .await?;
+        let mut res = Self::parse_result(&raw)?;
+        res.results.retain(|user| UserFilter::all(&filters, user));
+        Ok((res, raw))
+    }
+
+    /// Generate users with the api informations, along with the duration of the request
+    /// and the size of the response
+    ///
+    /// The duration includes the retries, if any
+    ///
+    /// ## Example:
+    /// ```no_run
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     use random_user::UserGenerator;
+    ///
+    ///     let generator = UserGenerator::new();
+    ///
+    ///     let (users, stats) = generator.get().fetch_timed(500).await.unwrap();
+    ///     println!("Fetched {} bytes in {:?}", stats.bytes, stats.duration);
+    /// }
+    /// ```
+    pub async fn fetch_timed(self, count: usize) -> Result<(RandomUserResult, FetchStats)> {
+        let start = Instant::now();
+        let filters = self.filters.clone();
+        let raw = self
+            .count(count)?
+            .with_info()
+            .send_with(Self::parse_raw)
+            .await?;
+        let stats = FetchStats {
+            duration: start.elapsed(),
+            bytes: raw.len(),
+        };
+        let mut res = Self::parse_result(&raw)?;
+        res.results.retain(|user| UserFilter::all(&filters, user));
+        Ok((res, stats))
+    }
+
+    /// Generate users
+    ///
+    /// `count` must be between 1 and [`MAX_RESULTS`](Self::MAX_RESULTS),
+    /// otherwise [`RandomUserError::InvalidCount`] is returned without sending the request
+    ///
+    /// ## Example:
+    /// ```
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     use random_user::{RandomUserError, UserGenerator};
+    ///
+    ///     let generator = UserGenerator::new();
+    ///
+    ///     let users = generator.get().fetch(0).await;
+    ///     assert!(matches!(users, Err(RandomUserError::InvalidCount(0))));
+    /// }
+    /// ```
+    pub async fn fetch(self, count: usize) -> Result<Vec<RandomUser>> {
+        if self.filters.is_empty() {
+            self.fetch_unfiltered(count).await
+        } else {
+            self.fetch_until(count, Self::FILTER_OVER_FETCH, |_| true)
+                .await
+        }
+    }
+
+    pub(crate) async fn fetch_unfiltered(self, count: usize) -> Result<Vec<RandomUser>> {
+        #[cfg(feature = "offline")]
+        let fallback = self.fallback();
+        let res = self
+            .count(count)?
+            .no_info()
+            .send::<Results<RandomUser>>()
+            .await;
+        #[cfg(feature = "offline")]
+        if let (Err(e), Some(fallback)) = (&res, fallback) {
+            if Fallback::applies(e) {
+                return Ok(fallback.users(count));
+            }
+        }
+        Ok(res?.results)
+    }
+
+    /// Generate any number of users, split into concurrent requests of at most
+    /// [`MAX_RESULTS`](Self::MAX_RESULTS) users
+    ///
+    /// The requests are pages of the same seeded dataset, so the users are all distinct.
+    /// A random seed is used if none is given. At most `concurrency` requests are sent at once.
+    /// Fails with [`RandomUserError::InvalidCount`] without sending any request
+    /// if there are more pages than a `u32` can number.
+    ///
+    /// ## Example:
+    /// ```no_run
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     use random_user::UserGenerator;
+    ///
+    ///     let generator = UserGenerator::new();
+    ///
+    ///     // 10 requests of 5000 users, 4 at a time
+    ///     let users = generator.get().fetch_many(50000, 4).await.unwrap();
+    /// }
+    /// ```
+    pub async fn fetch_many(self, count: usize, concurrency: usize) -> Result<Vec<RandomUser>> {
+        self.fetch_pages(count, concurrency, |_, _| {}).await
+    }
+
+    /// Generate any number of users like [`fetch_many`](Self::fetch_many),
+    /// one request at a time, calling `on_progress` with the number of users
+    /// fetched so far and the total after each request
+    ///
+    /// ## Example:
+    /// ```no_run
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     use random_user::UserGenerator;
+    ///
+    ///     let generator = UserGenerator::new();
+    ///
+    ///     let users = generator
+    ///         .get()
+    ///         .fetch_with_progress(20000, |done, total| println!("{done}/{total}"))
+    ///         .await
+    ///         .unwrap();
+    /// }
+    /// ```
+    pub async fn fetch_with_progress(
+        self,
+        count: usize,
+        on_progress: impl FnMut(usize, usize),
+    ) -> Result<Vec<RandomUser>> {
+        self.fetch_pages(count, 1, on_progress).await
+    }
+
+    /// Generate any number of users like [`fetch_many`](Self::fetch_many), at most `concurrency`
+    /// requests at a time, keeping the users of the successful requests along with the errors of the others
+    ///
+    /// Fewer users than requested are returned if any request failed.
+    ///
+    /// ## Example:
+    /// ```no_run
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     use random_user::UserGenerator;
+    ///
+    ///     let generator = UserGenerator::new();
+    ///
+    ///     // 10 requests of 5000 users, 4 at a time
+    ///     let (users, errors) = generator.get().fetch_lenient(50000, 4).await;
+    ///     for error in errors {
+    ///         eprintln!("Missing users: {error}");
+    ///     }
+    ///     println!("Got {} users", users.len());
+    /// }
+    /// ```
+    pub async fn fetch_lenient(
+        self,
+        count: usize,
+        concurrency: usize,
+    ) -> (Vec<RandomUser>, Vec<RandomUserError>) {
+        if let Err(e) = Self::check_page_count(count) {
+            return (Vec::new(), vec![e]);
+        }
+        let pages = self.page_stream(count, concurrency);
+        let mut pages = std::pin::pin!(pages);
+        let mut users = Vec::with_capacity(count.min(Self::MAX_RESULTS));
+        let mut errors = Vec::new();
+        while let Some(page) = pages.next().await {
+            match page {
+                Ok(page) => users.extend(page),
+                Err(e) => errors.push(e),
+            }
+        }
+        (users, errors)
+    }
+
+    /// Generate users with as many men as women, shuffled together
+    ///
+    /// The api can only filter a single gender per request, so this costs two requests.
+    /// With an odd count, there is one more woman than men.
+    ///
+    /// ## Example:
+    /// ```no_run
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     use random_user::UserGenerator;
+    ///
+    ///     let generator = UserGenerator::new();
+    ///
+    ///     // 5 men and 5 women
+    ///     let users = generator.get().balanced_genders(10).await.unwrap();
+    /// }
+    /// ```
+    pub async fn balanced_genders(self, count: usize) -> Result<Vec<RandomUser>> {
+        let males = count / 2;
+        let females = count - males;
+        let male_req = self.clone().gender(Gender::Male);
+        let female_req = self.gender(Gender::Female);
+        let (mut users, male_users) = future::try_join(
+            Self::fetch_or_empty(female_req, females),
+            Self::fetch_or_empty(male_req, males),
+        )
+        .await?;
+        users.extend(male_users);
+        users.shuffle(&mut rand::thread_rng());
+        Ok(users)
+    }
+
+    /// Generate users with nationalities following the given weights, shuffled together
+    ///
+    /// The api can't weight nationalities, so the count is split proportionally to the weights,
+    /// costing one request per nationality. Fails with [`RandomUserError::InvalidCount`]
+    /// without sending any request if a nationality gets more than [`MAX_RESULTS`](Self::MAX_RESULTS) users.
+    ///
+    /// ## Example:
+    /// ```no_run
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     use random_user::{Nationality, UserGenerator};
+    ///
+    ///     let generator = UserGenerator::new();
+    ///
+    ///     // 70 Americans and 30 French
+    ///     let users = generator
+    ///         .get()
+    ///         .weighted_nationalities(100, &[(Nationality::American, 7), (Nationality::French, 3)])
+    ///         .await
+    ///         .unwrap();
+    /// }
+    /// ```
+    pub async fn weighted_nationalities(
+        self,
+        count: usize,
+        weights: &[(Nationality, u32)],
+    ) -> Result<Vec<RandomUser>> {
+        let counts = split_count(count, weights.iter().map(|(_, weight)| *weight))
+            .ok_or(RandomUserError::InvalidWeights)?;
+        if let Some(&count) = counts.iter().find(|&&count| count > Self::MAX_RESULTS) {
+            return Err(RandomUserError::InvalidCount(count));
+        }
+        let requests = weights
+            .iter()
+            .zip(counts)
+            .map(|((nat, _), count)| Self::fetch_or_empty(self.clone().nationality(*nat), count));
+        let mut users: Vec<RandomUser> = future::try_join_all(requests)
+            .await?
+            .into_iter()
+            .flatten()
+            .collect();
+        users.shuffle(&mut rand::thread_rng());
+        Ok(users)
+    }
+
+    /// Generate users whose age is between `min` and `max`, inclusive
+    ///
+    /// The api can't filter ages, so more users than needed are requested and filtered,
+    /// assuming ages are spread evenly between about 20 and 80.
+    /// Fails with [`RandomUserError::NotEnoughUsers`] if there are still too few after several requests.
+    ///
+    /// ## Example:
+    /// ```no_run
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     use random_user::UserGenerator;
+    ///
+    ///     let generator = UserGenerator::new();
+    ///
+    ///     let users = generator.get().fetch_in_age_range(10, 25, 30).await.unwrap();
+    ///     assert!(users.iter().all(|user| (25..=30).contains(&user.birthday.age)));
+    /// }
+    /// ```
+    pub async fn fetch_in_age_range(
+        self,
+        count: usize,
+        min: u8,
+        max: u8,
+    ) -> Result<Vec<RandomUser>> {
+        if min > max {
+            return Err(RandomUserError::InvalidAgeRange { min, max });
+        }
+        let over_fetch = Self::age_over_fetch(min, max);
+        let ages = i32::from(min)..=i32::from(max);
+        self.fetch_until(count, over_fetch, |user| ages.contains(&user.birthday.age))
+            .await
+    }
+
+    /// Generate users spread across age brackets, given as `(min, max, count)` with inclusive ages
+    ///
+    /// Users are returned bracket after bracket, and a user whose age fits several brackets
+    /// goes to the first one still missing users. Like [`fetch_in_age_range`](Self::fetch_in_age_range),
+    /// more users than needed are requested and filtered, failing with
+    /// [`RandomUserError::NotEnoughUsers`] if some brackets are still missing users after several requests,
+    /// or after the first one when seeded. Each request asks for at most [`MAX_RESULTS`](Self::MAX_RESULTS)
+    /// users, so larger totals are topped up over the requests.
+    ///
+    /// ## Example:
+    /// ```no_run
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     use random_user::UserGenerator;
+    ///
+    ///     let generator = UserGenerator::new();
+    ///
+    ///     let users = generator
+    ///         .get()
+    ///         .fetch_age_buckets(&[(18, 29, 5), (30, 49, 10), (50, 120, 5)])
+    ///         .await
+    ///         .unwrap();
+    ///     assert_eq!(users.len(), 20);
+    /// }
+    /// ```
+    pub async fn fetch_age_buckets(self, buckets: &[(u8, u8, usize)]) -> Result<Vec<RandomUser>> {
+        if let Some(&(min, max, _)) = buckets.iter().find(|(min, max, _)| min > max) {
+            return Err(RandomUserError::InvalidAgeRange { min, max });
+        }
+        let requested = buckets
+            .iter()
+            .fold(0usize, |total, (_, _, count)| total.saturating_add(*count));
+        let mut bins: Vec<Vec<RandomUser>> = buckets
+            .iter()
+            .map(|(_, _, count)| Vec::with_capacity((*count).min(Self::MAX_RESULTS)))
+            .collect();
+        let mut found = 0;
+        for _ in 0..Self::FILTER_ATTEMPTS {
+            let missing = requested - found;
+            if missing == 0 {
+                break;
+            }
+            let over_fetch = buckets
+                .iter()
+                .zip(&bins)
+                .filter(|((_, _, count), bin)| bin.len() < *count)
+                .map(|((min, max, _), _)| Self::age_over_fetch(*min, *max))
+                .max()
+                .unwrap_or(1);
+            let batch = missing.saturating_mul(over_fetch).min(Self::MAX_RESULTS);
+            let fetched = self
+                .clone()
+                .fetch_unfiltered(batch.max(missing.min(Self::MAX_RESULTS)))
+                .await?;
+            for user in fetched
+                .into_iter()
+                .filter(|user| self.matches_filters(user))
+            {
+                let age = user.birthday.age;
+                let bin = buckets
+                    .iter()
+                    .zip(&mut bins)
+                    .find(|((min, max, count), bin)| {
+                        bin.len() < *count && (i32::from(*min)..=i32::from(*max)).contains(&age)
+                    });
+                if let Some((_, bin)) = bin {
+                    bin.push(user);
+                    found += 1;
+                }
+            }
+            // A seeded request would return the same users again
+            if self.seed.is_some() {
+                break;
+            }
+        }
+        if found < requested {
+            return Err(RandomUserError::NotEnoughUsers { requested, found });
+        }
+        Ok(bins.into_iter().flatten().collect())
+    }
+
+    /// Generate separate groups of users, one per nationality
+    ///
+    /// Costs one request per entry, sent concurrently.
+    ///
+    /// ## Example:
+    /// ```no_run
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     use random_user::{Nationality, UserGenerator};
+    ///
+    ///     let generator = UserGenerator::new();
+    ///
+    ///     let groups = generator
+    ///         .get()
+    ///         .fetch_grouped(&[(Nationality::American, 5), (Nationality::French, 5)])
+    ///         .await
+    ///         .unwrap();
+    ///     assert_eq!(groups[&Nationality::French].len(), 5);
+    /// }
+    /// ```
+    pub async fn fetch_grouped(
+        self,
+        specs: &[(Nationality, usize)],
+    ) -> Result<HashMap<Nationality, Vec<RandomUser>>> {
+        let requests = specs
+            .iter()
+            .map(|(nat, count)| Self::fetch_or_empty(self.clone().nationality(*nat), *count));
+        let mut groups: HashMap<Nationality, Vec<RandomUser>> = HashMap::new();
+        for ((nat, _), users) in specs.iter().zip(future::try_join_all(requests).await?) {
+            groups.entry(*nat).or_default().extend(users);
+        }
+        Ok(groups)
+    }
+
+    /// Generate users with unique usernames and emails
+    ///
+    /// Duplicates are dropped and replaced by new users, failing with
+    /// [`RandomUserError::NotEnoughUsers`] if there are still some after several requests,
+    /// which always happens with a [`seed`](Self::seed).
+    ///
+    /// ## Example:
+    /// ```no_run
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     use random_user::UserGenerator;
+    ///
+    ///     let generator = UserGenerator::new();
+    ///
+    ///     let users = generator.get().fetch_unique(1000).await.unwrap();
+    /// }
+    /// ```
+    pub async fn fetch_unique(self, count: usize) -> Result<Vec<RandomUser>> {
+        let mut usernames = HashSet::new();
+        let mut emails = HashSet::new();
+        self.fetch_until(count, 1, |user| {
+            if usernames.contains(&user.login.username) || emails.contains(&user.email) {
+                return false;
+            }
+            usernames.insert(user.login.username.clone());
+            emails.insert(user.email.clone());
+            true
+        })
+        .await
+    }
+
+    /// Generate 1 user
+    ///
+    /// The api sometimes returns no user, the request is then sent once more
+    /// before failing with [`RandomUserError::EmptyResult`].
+    ///
+    /// ## Example:
+    /// ```
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     use random_user::{RandomUserError, RandomUserInfo, RandomUserResult, UserGenerator};
+    ///
+    ///     let empty = RandomUserResult {
+    ///         results: Vec::new(),
+    ///         info: RandomUserInfo {
+    ///             seed: "abc".to_owned(),
+    ///             results: 0,
+    ///             page: 1,
+    ///             version: "1.4".to_owned(),
+    ///         },
+    ///     };
+    ///     let generator = UserGenerator::mock(vec![empty.clone(), empty]);
+    ///
+    ///     let res = generator.get().fetch_one().await;
+    ///     assert!(matches!(res, Err(RandomUserError::EmptyResult)));
+    /// }
+    /// ```
+    pub async fn fetch_one(self) -> Result<RandomUser> {
+        match self.clone().fetch(1).await?.pop() {
+            Some(user) => Ok(user),
+            None => self
+                .fetch(1)
+                .await?
+                .pop()
+                .ok_or(RandomUserError::EmptyResult),
+        }
+    }
+
+    /// Generate the first user of a seed, repeated `n` times
+    ///
+    /// Each user of a seed is different, so this sends a single request and clones the user.
+    ///
+    /// ## Example:
+    /// ```no_run
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     use random_user::UserGenerator;
+    ///
+    ///     let generator = UserGenerator::new();
+    ///
+    ///     let users = generator.get().fetch_one_repeated("foobar", 3).await.unwrap();
+    ///     assert!(users.iter().all(|user| *user == users[0]));
+    /// }
+    /// ```
+    pub async fn fetch_one_repeated(self, seed: &str, n: usize) -> Result<Vec<RandomUser>> {
+        if n == 0 {
+            return Ok(Vec::new());
+        }
+        let user = self.seed(seed).fetch_one().await?;
+        Ok(vec![user; n])
+    }
+
+    /// Generate 1 user along with the seed used, to reproduce it later
+    ///
+    /// Without a [`seed`](Self::seed), the api picks one at random.
+    ///
+    /// ## Example:
+    /// ```no_run
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     use random_user::UserGenerator;
+    ///
+    ///     let generator = UserGenerator::new();
+    ///
+    ///     let (user, seed) = generator.get().fetch_one_with_seed().await.unwrap();
+    ///     let same_user = generator.fetch_seeded(&seed).await.unwrap();
+    ///     assert_eq!(user, same_user);
+    /// }
+    /// ```
+    pub async fn fetch_one_with_seed(self) -> Result<(RandomUser, String)> {
+        if self.filters.is_empty() {
+            let mut result = self.fetch_with_info(1).await?;
+            let user = result.results.pop().ok_or(RandomUserError::EmptyResult)?;
+            return Ok((user, result.info.seed));
+        }
+        let attempts = if self.seed.is_some() {
+            1
+        } else {
+            Self::FILTER_ATTEMPTS
+        };
+        for _ in 0..attempts {
+            let mut result = self.clone().fetch_with_info(1).await?;
+            if let Some(user) = result.results.pop() {
+                return Ok((user, result.info.seed));
+            }
+        }
+        Err(RandomUserError::NotEnoughUsers {
+            requested: 1,
+            found: 0,
+        })
+    }
+
+    /// Generate users in the csv format
+    ///
+    /// ## Example:
+    /// ```no_run
+    /// // Save 1000 users to a csv file
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     use random_user::UserGenerator;
+    ///
+    ///     let generator = UserGenerator::new();
+    ///
+    ///     let csv = generator.get().fetch_csv(1000).await.unwrap();
+    ///     std::fs::write("users.csv", csv).unwrap();
+    /// }
+    /// ```
+    pub async fn fetch_csv(self, count: usize) -> Result<String> {
+        self.fetch_raw(count, Format::Csv).await
+    }
+
+    /// Generate users in the csv format, writing them to `writer` as they are received
+    ///
+    /// Unlike [`fetch_csv`](Self::fetch_csv), the whole csv is never held in memory.
+    /// If the download fails midway, what was already received stays written.
+    ///
+    /// ## Example:
+    /// ```no_run
+    /// // Save 5000 users to a csv file
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     use random_user::UserGenerator;
+    ///
+    ///     let generator = UserGenerator::new();
+    ///
+    ///     let file = std::fs::File::create("users.csv").unwrap();
+    ///     generator.get().fetch_csv_to(5000, file).await.unwrap();
+    /// }
+    /// ```
+    pub async fn fetch_csv_to<W: Write>(self, count: usize, mut writer: W) -> Result<()> {
+        let mut response = self
+            .count(count)?
+            .query("format", Format::Csv.as_str())
+            .send_with(Self::check_raw)
+            .await?;
+        while let Some(chunk) = response.chunk().await? {
+            writer.write_all(&chunk)?;
+        }
+        writer.flush()?;
+        Ok(())
+    }
+
+    /// Send the request as is and return the response of the api, without checking its status
+    ///
+    /// Unlike the other methods, the request is sent once, without retries or offline
+    /// fallback, and the number of users is left to the api unless set with
+    /// [`raw_param`](Self::raw_param).
+    ///
+    /// ## Example:
+    /// ```
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     use random_user::UserGenerator;
+    ///
+    ///     let generator = UserGenerator::mock(Vec::new());
+    ///
+    ///     let response = generator.get().raw_param("results", "5").send_raw().await.unwrap();
+    ///     assert!(response.status().is_success());
+    ///     assert_eq!(response.headers()["content-type"], "application/json");
+    /// }
+    /// ```
+    pub async fn send_raw(self) -> Result<reqwest::Response> {
+        self.transport.send(self.build().build()?).await
+    }
+
+    /// Generate users in the given format, without parsing them
+    ///
+    /// ## Example:
+    /// ```no_run
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     use random_user::{Format, UserGenerator};
+    ///
+    ///     let generator = UserGenerator::new();
+    ///
+    ///     let xml = generator.get().fetch_raw(5, Format::Xml).await.unwrap();
+    ///     println!("{xml}");
+    /// }
+    /// ```
+    pub async fn fetch_raw(self, count: usize, format: Format) -> Result<String> {
+        self.count(count)?
+            .query("format", format.as_str())
+            .send_with(Self::parse_raw)
+            .await
+    }
+
+    /// Generate users that may miss some fields,
+    /// see [`include`](Self::include) and [`exclude`](Self::exclude)
+    pub async fn fetch_partial(self, count: usize) -> Result<Vec<PartialUser>> {
+        let res: Results<PartialUser> = self.count(count)?.no_info().send().await?;
+        Ok(res.results)
+    }
+
+    /// Generate only email addresses, without fetching the other fields
+    ///
+    /// ## Example:
+    /// ```no_run
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     use random_user::UserGenerator;
+    ///
+    ///     let generator = UserGenerator::new();
+    ///
+    ///     let emails = generator.get().fetch_emails(100).await.unwrap();
+    /// }
+    /// ```
+    pub async fn fetch_emails(self, count: usize) -> Result<Vec<String>> {
+        let users = self.include(&[Field::Email]).fetch_partial(count).await?;
+        Ok(users.into_iter().filter_map(|user| user.email).collect())
+    }
+
+    /// Generate users without their credentials, see [`without_credentials`](Self::without_credentials)
+    ///
+    /// ## Example:
+    /// ```no_run
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     use random_user::UserGenerator;
+    ///
+    ///     let generator = UserGenerator::new();
+    ///
+    ///     for user in generator.get().fetch_lite(5).await.unwrap() {
+    ///         println!("{} {}", user.name, user.email);
+    ///     }
+    /// }
+    /// ```
+    pub async fn fetch_lite(self, count: usize) -> Result<Vec<RandomUserLite>> {
+        let res: Results<RandomUserLite> = self
+            .without_credentials()
+            .count(count)?
+            .no_info()
+            .send()
+            .await?;
+        Ok(res.results)
+    }
+
+    /// Generate users lazily, walking the pages of a seeded dataset
+    ///
+    /// Users are fetched `page_size` at a time, and the stream ends once a page
+    /// returns fewer users than requested.
+    ///
+    /// A network, timeout or rate limit error is yielded as an `Err` item, and the failed page is requested
+    /// again if the stream is polled further. Any other error ends the stream.
+    ///
+    /// ## Example:
+    /// ```no_run
+    /// // Get 10000 users, 500 per request
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     use futures_util::StreamExt;
+    ///     use random_user::UserGenerator;
+    ///
+    ///     let generator = UserGenerator::new();
+    ///
+    ///     let users = generator.get().stream("foobar", 500).take(10000);
+    ///     let mut users = std::pin::pin!(users);
+    ///
+    ///     while let Some(user) = users.next().await {
+    ///         println!("{:?}", user.unwrap());
+    ///     }
+    /// }
+    /// ```
+    pub fn stream(self, seed: &str, page_size: usize) -> impl Stream<Item = Result<RandomUser>> {
+        self.pages(seed, page_size)
+            .map_ok(|page| stream::iter(page.results.into_iter().map(Ok)))
+            .try_flatten()
+    }
+
+    /// Generate whole pages of a seeded dataset lazily, with the api informations
+    ///
+    /// Works like [`stream`](Self::stream), each page telling its number in `info.page`,
+    /// and the stream ends after a page returning fewer users than requested.
+    ///
+    /// ## Example:
+    /// ```no_run
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     use futures_util::StreamExt;
+    ///     use random_user::UserGenerator;
+    ///
+    ///     let generator = UserGenerator::new();
+    ///
+    ///     let pages = generator.get().pages("foobar", 500).take(20);
+    ///     let mut pages = std::pin::pin!(pages);
+    ///
+    ///     while let Some(page) = pages.next().await {
+    ///         let page = page.unwrap();
+    ///         println!("Page {}: {} users", page.info.page, page.results.len());
+    ///     }
+    /// }
+    /// ```
+    pub fn pages(
+        self,
+        seed: &str,
+        page_size: usize,
+    ) -> impl Stream<Item = Result<RandomUserResult>> {
+        let state = PageState {
+            builder: self,
+            seed: seed.to_owned(),
+            page_size,
+            page: 1,
+            done: false,
+        };
+        stream::unfold(state, |mut state| async move {
+            if state.done {
+                return None;
+            }
+            let builder = state.builder.clone().seed(&state.seed).page(state.page);
+            match builder.fetch_info_unfiltered(state.page_size).await {
+                Ok(mut result) => {
+                    state.done = result.results.len() < state.page_size;
+                    result
+                        .results
+                        .retain(|user| state.builder.matches_filters(user));
+                    state.page += 1;
+                    Some((Ok(result), state))
+                }
+                Err(e) => {
+                    state.done = !matches!(
+                        e,
+                        RandomUserError::Reqwest(_)
+                            | RandomUserError::Timeout
+                            | RandomUserError::RateLimited { .. }
+                    );
+                    Some((Err(e), state))
+                }
+            }
+        })
+    }
+
+    /// Generate users of a seeded dataset lazily like [`stream`](Self::stream),
+    /// requesting the next page while the current one is consumed
+    ///
+    /// At most one page is fetched ahead, so at most `2 * page_size` users are buffered.
+    /// The next page is only requested after a full one, and dropping the stream cancels it.
+    /// Any error is yielded as an `Err` item and ends the stream, set a
+    /// [`RetryPolicy`] on the generator to retry failed pages.
+    ///
+    /// ## Example:
+    /// ```no_run
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     use futures_util::StreamExt;
+    ///     use random_user::UserGenerator;
+    ///
+    ///     let generator = UserGenerator::new();
+    ///
+    ///     let users = generator.get().prefetched("foobar", 500).take(10000);
+    ///     let mut users = std::pin::pin!(users);
+    ///
+    ///     while let Some(user) = users.next().await {
+    ///         // Slow processing, the next page is already on its way
+    ///         println!("{:?}", user.unwrap());
+    ///     }
+    /// }
+    /// ```
+    pub fn prefetched(
+        self,
+        seed: &str,
+        page_size: usize,
+    ) -> impl Stream<Item = Result<RandomUser>> {
+        let template = self.seed(seed);
+        // The page to yield next, along with its request if already sent, until a short page
+        stream::unfold(Some((1, None)), move |next| {
+            let template = template.clone();
+            async move {
+                let (page, request) = next?;
+                let request =
+                    request.unwrap_or_else(|| Prefetch::spawn(&template, page, page_size));
+                let mut result = request.await;
+                let next = match &result {
+                    Ok(result) if result.results.len() >= page_size => Some((
+                        page + 1,
+                        Some(Prefetch::spawn(&template, page + 1, page_size)),
+                    )),
+                    _ => None,
+                };
+                if let Ok(result) = &mut result {
+                    result.results.retain(|user| template.matches_filters(user));
+                }
+                Some((result, next))
+            }
+        })
+        .map_ok(|page| stream::iter(page.results.into_iter().map(Ok)))
+        .try_flatten()
+    }
+
+    /// Url requested with the current filters, to log or check it
+    ///
+    /// The number of users is only added by the fetch methods.
+    ///
+    /// ## Example:
+    /// ```
+    /// use random_user::{Nationality, UserGenerator};
+    ///
+    /// let url = UserGenerator::new()
+    ///     .get()
+    ///     .nationalities(&[Nationality::French, Nationality::British])
+    ///     .seed("foobar")
+    ///     .build_url()
+    ///     .unwrap();
+    ///
+    /// assert_eq!(url.path(), "/api/1.4/");
+    /// assert!(url.query_pairs().any(|(key, value)| key == "nat" && value == "FR,GB"));
+    /// assert!(url.query_pairs().any(|(key, value)| key == "seed" && value == "foobar"));
+    /// ```
+    pub fn build_url(&self) -> Result<Url> {
+        Ok(self.build().build()?.url().clone())
+    }
+
+    /// Walk the pages of a seeded dataset one at a time, see [`Cursor`]
+    pub fn cursor(self, seed: &str, page_size: usize) -> Cursor {
+        Cursor::new(self, seed, page_size)
+    }
+
+    fn query(mut self, key: &str, value: impl ToString) -> Self {
+        self.query.push((key.to_owned(), value.to_string()));
+        self
+    }
+
+    /// Build the request with all the query parameters
+    fn build(&self) -> reqwest::RequestBuilder {
+        let mut req = self.client.get(self.url.clone()).query(&self.query);
+        if let Some(user_agent) = &self.user_agent {
+            req = req.header(USER_AGENT, user_agent.clone());
+        }
+        if let Some(timeout) = self.timeout {
+            req = req.timeout(timeout);
+        }
+        if let Some(gender) = &self.gender {
+            req = req.query(&[("gender", gender.as_str())]);
+        }
+        if !self.nationalities.is_empty() {
+            req = req.query(&[("nat", Nationality::join(&self.nationalities))]);
+        }
+        if !self.include.is_empty() {
+            req = req.query(&[("inc", Field::join(&self.include))]);
+        }
+        if !self.exclude.is_empty() {
+            req = req.query(&[("exc", Field::join(&self.exclude))]);
+        }
+        if let Some(seed) = &self.seed {
+            req = req.query(&[("seed", seed)]);
+        }
+        if self.no_info {
+            req = req.query(&[("noinfo", "")]);
+        }
+        req
+    }
+
+    /// Filters to generate users locally with, when an offline fallback is set
+    #[cfg(feature = "offline")]
+    fn fallback(&self) -> Option<Fallback> {
+        let seed = self.seed.as_ref().or(self.offline_seed.as_ref())?;
+        let page = self
+            .query
+            .iter()
+            .rev()
+            .find(|(key, _)| key == "page")
+            .and_then(|(_, page)| page.parse().ok())
+            .unwrap_or(1);
+        Some(Fallback {
+            seed: seed.clone(),
+            page,
+            gender: self.gender.clone(),
+            nationalities: self.nationalities.clone(),
+        })
+    }
+
+    fn with_info(self) -> Self {
+        Self {
+            no_info: false,
+            ..self
+        }
+    }
+
+    /// Request `count` users as pages of the same seeded dataset, in order
+    fn page_stream(
+        self,
+        count: usize,
+        concurrency: usize,
+    ) -> impl Stream<Item = Result<Vec<RandomUser>>> {
+        let seed = self.seed.clone().unwrap_or_else(random_seed);
+        let template = self.seed(&seed);
+        let pages = (0..count.div_ceil(Self::MAX_RESULTS)).map(move |index| {
+            let page_size = Self::MAX_RESULTS.min(count - index * Self::MAX_RESULTS);
+            let builder = template.clone().page(index as u32 + 1);
+            builder.fetch_page(page_size)
+        });
+        stream::iter(pages).buffered(concurrency.max(1))
+    }
+
+    /// Check that the pages of `count` users can be numbered
+    fn check_page_count(count: usize) -> Result<()> {
+        if count.div_ceil(Self::MAX_RESULTS) > u32::MAX as usize {
+            return Err(RandomUserError::InvalidCount(count));
+        }
+        Ok(())
+    }
+
+    /// Fetch `count` users as pages of the same seeded dataset, reporting after each page
+    async fn fetch_pages(
+        self,
+        count: usize,
+        concurrency: usize,
+        mut on_progress: impl FnMut(usize, usize),
+    ) -> Result<Vec<RandomUser>> {
+        Self::check_page_count(count)?;
+        let pages = self.page_stream(count, concurrency);
+        let mut pages = std::pin::pin!(pages);
+        let mut users = Vec::with_capacity(count.min(Self::MAX_RESULTS));
+        while let Some(page) = pages.try_next().await? {
+            users.extend(page);
+            on_progress(users.len(), count);
+        }
+        Ok(users)
+    }
+
+    /// Fetch users until `count` of them match the filters and are kept by `keep`,
+    /// requesting `over_fetch` times the missing ones
+    ///
+    /// `keep` only sees the users matching the filters, and a seeded builder sends a single request.
+    async fn fetch_until(
+        self,
+        count: usize,
+        over_fetch: usize,
+        mut keep: impl FnMut(&RandomUser) -> bool,
+    ) -> Result<Vec<RandomUser>> {
+        let mut users = Vec::with_capacity(count.min(Self::MAX_RESULTS));
+        for _ in 0..Self::FILTER_ATTEMPTS {
+            let missing = count - users.len();
+            let batch = missing.saturating_mul(over_fetch).min(Self::MAX_RESULTS);
+            let fetched = self.clone().fetch_unfiltered(batch.max(missing)).await?;
+            users.extend(
+                fetched
+                    .into_iter()
+                    .filter(|user| self.matches_filters(user) && keep(user))
+                    .take(missing),
+            );
+            // A seeded request would return the same users again
+            if users.len() == count || self.seed.is_some() {
+                break;
+            }
+        }
+        if users.len() == count {
+            return Ok(users);
+        }
+        Err(RandomUserError::NotEnoughUsers {
+            requested: count,
+            found: users.len(),
+        })
+    }
+
+    /// Fetch a single request of users, dropping the ones not matching the filters
+    /// instead of topping them up
+    async fn fetch_page(self, count: usize) -> Result<Vec<RandomUser>> {
+        let filters = self.filters.clone();
+        let mut users = self.fetch_unfiltered(count).await?;
+        users.retain(|user| UserFilter::all(&filters, user));
+        Ok(users)
+    }
+
+    /// Whether the user matches all the predicates given to [`filter`](Self::filter)
+    pub(crate) fn matches_filters(&self, user: &RandomUser) -> bool {
+        UserFilter::all(&self.filters, user)
+    }
+
+    /// Over-fetch factor to find users between `min` and `max` years old
+    fn age_over_fetch(min: u8, max: u8) -> usize {
+        Self::AGE_SPAN / (usize::from(max - min) + 1).min(Self::AGE_SPAN)
+    }
+
+    async fn fetch_or_empty(self, count: usize) -> Result<Vec<RandomUser>> {
+        match count {
+            0 => Ok(Vec::new()),
+            count => self.fetch(count).await,
+        }
+    }
+
+    /// Request `count` users, which must be between 1 and [`MAX_RESULTS`](Self::MAX_RESULTS)
+    fn count(self, count: usize) -> Result<Self> {
+        if count == 0 || count > Self::MAX_RESULTS {
+            return Err(RandomUserError::InvalidCount(count));
+        }
+        Ok(self.query("results", count))
+    }
+
+    async fn send<T: DeserializeOwned>(self) -> Result<T> {
+        let rsp = self.send_with(Self::parse_response).await?;
+        match rsp {
+            ApiResponse::Error(e) => Err(RandomUserError::Api(e)),
+            ApiResponse::Result(res) => Ok(res),
+        }
+    }
+
+    /// Send the request, retrying according to the policy,
+    /// and read the response with the given function
+    async fn send_with<T, F, Fut>(self, read: F) -> Result<T>
+    where
+        F: Fn(reqwest::Response) -> Fut,
+        Fut: Future<Output = Result<T>>,
+    {
+        let mut attempt = 1;
+        loop {
+            match Self::send_once(&self.transport, self.build(), &read).await {
+                Err(e) if attempt < self.retry.max_attempts() && self.retry.is_retryable(&e) => {
+                    tokio::time::sleep(self.retry.delay(attempt, &e)).await;
+                    attempt += 1;
+                }
+                res => return res,
+            }
+        }
+    }
+
+    async fn send_once<T, F, Fut>(
+        transport: &Arc<dyn UserTransport>,
+        req: reqwest::RequestBuilder,
+        read: &F,
+    ) -> Result<T>
+    where
+        F: Fn(reqwest::Response) -> Fut,
+        Fut: Future<Output = Result<T>>,
+    {
+        let api_rsp = transport.send(req.build()?).await?;
+        read(Self::check_status(api_rsp).await?).await
+    }
+
+    /// Turn an error status into an error, with the body of the response
+    async fn check_status(response: reqwest::Response) -> Result<reqwest::Response> {
+        let status = response.status();
+        if status == StatusCode::TOO_MANY_REQUESTS {
+            let retry_after = response
+                .headers()
+                .get(RETRY_AFTER)
+                .and_then(|value| value.to_str().ok())
+                .and_then(parse_retry_after);
+            return Err(RandomUserError::RateLimited { retry_after });
+        }
+        if status.is_client_error() || status.is_server_error() {
+            let body = response.text().await?;
+            return Err(RandomUserError::Http { status, body });
+        }
+        Ok(response)
+    }
+
+    fn content_type(response: &reqwest::Response) -> Result<String> {
+        Ok(response
+            .headers()
+            .get("content-type")
+            .ok_or(RandomUserError::BadFormat)?
+            .to_str()
+            .map_err(|_| RandomUserError::BadFormat)?
+            .to_owned())
+    }
+
+    /// Fail with the error message of the api, returned as plain text for the raw formats
+    async fn check_raw(response: reqwest::Response) -> Result<reqwest::Response> {
+        if Self::content_type(&response)?.contains("text/plain") {
+            return Err(RandomUserError::Api(response.text().await?));
+        }
+        Ok(response)
+    }
+
+    async fn parse_raw(response: reqwest::Response) -> Result<String> {
+        Ok(Self::check_raw(response).await?.text().await?)
+    }
+
+    fn parse_result(raw: &str) -> Result<RandomUserResult> {
+        match serde_json::from_str(raw) {
+            Ok(RandomUserResponse::Result(res)) => Ok(res),
+            Ok(RandomUserResponse::Error(e)) => Err(RandomUserError::Api(e)),
+            Err(source) => Err(RandomUserError::Parse {
+                source,
+                body: raw.to_owned(),
+            }),
+        }
+    }
+
+    async fn parse_response<T: DeserializeOwned>(
+        response: reqwest::Response,
+    ) -> Result<ApiResponse<T>> {
+        let content_type = Self::content_type(&response)?;
+        let text = response.text().await?;
+        match content_type {
+            ct if ct.contains("text/plain") => Ok(ApiResponse::Error(text)),
+            ct if ct.contains("application/json") => serde_json::from_str::<ApiResponse<T>>(&text)
+                .map_err(|source| RandomUserError::Parse { source, body: text }),
+            _ => Err(RandomUserError::BadFormat),
+        }
+    }
+}
+
+/// Split `count` proportionally to the weights, using the largest remainders
+/// so that the parts add up to `count`
+///
+/// Returns `None` if the weights add up to 0
+fn split_count(count: usize, weights: impl Iterator<Item = u32> + Clone) -> Option<Vec<usize>> {
+    let total: u128 = weights.clone().map(u128::from).sum();
+    if total == 0 {
+        return None;
+    }
+    let shares: Vec<(usize, u128)> = weights
+        .map(|weight| {
+            let share = count as u128 * u128::from(weight);
+            ((share / total) as usize, share % total)
+        })
+        .collect();
+    let mut counts: Vec<usize> = shares.iter().map(|(count, _)| *count).collect();
+    let mut by_remainder: Vec<usize> = (0..shares.len()).collect();
+    by_remainder.sort_by_key(|&index| std::cmp::Reverse(shares[index].1));
+    let missing = count - counts.iter().sum::<usize>();
+    for index in by_remainder.into_iter().take(missing) {
+        counts[index] += 1;
+    }
+    Some(counts)
+}
+
+/// Generate a seed for requests that must share the same dataset
+fn random_seed() -> String {
+    rand::thread_rng()
+        .sample_iter(&Alphanumeric)
+        .take(16)
+        .map(char::from)
+        .collect()
+}
+
+/// Check that an api version is formatted like `1.4`
+fn is_version(version: &str) -> bool {
+    let is_number = |s: &str| !s.is_empty() && s.chars().all(|c| c.is_ascii_digit());
+    version
+        .split_once('.')
+        .is_some_and(|(major, minor)| is_number(major) && is_number(minor))
+}
+
+/// Value of an environment variable, `None` if it isn't set
+fn env_var(name: &str) -> Result<Option<String>> {
+    match std::env::var(name) {
+        Ok(value) => Ok(Some(value)),
+        Err(std::env::VarError::NotPresent) => Ok(None),
+        Err(std::env::VarError::NotUnicode(value)) => Err(RandomUserError::InvalidEnv {
+            name: name.to_owned(),
+            value: value.to_string_lossy().into_owned(),
+        }),
+    }
+}
+
+/// Parse a `Retry-After` header, either as a number of seconds or as an http date
+fn parse_retry_after(value: &str) -> Option<Duration> {
+    if let Ok(seconds) = value.trim().parse() {
+        return Some(Duration::from_secs(seconds));
+    }
+    let date = DateTime::parse_from_rfc2822(value).ok()?;
+    (date.with_timezone(&Utc) - Utc::now()).to_std().ok()
+}
+
+/// Predicate of [`UserGeneratorBuilder::filter`]
+#[derive(Clone)]
+struct UserFilter(Arc<dyn Fn(&RandomUser) -> bool + Send + Sync>);
+
+impl UserFilter {
+    /// Whether the user matches all the filters
+    fn all(filters: &[Self], user: &RandomUser) -> bool {
+        filters.iter().all(|filter| (filter.0)(user))
+    }
+}
+
+impl fmt::Debug for UserFilter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("UserFilter")
+    }
+}
+
+/// Users of a response requested without the api informations
+#[derive(Deserialize)]
+struct Results<T> {
+    results: Vec<T>,
+}
+
+/// Page requested ahead by [`UserGeneratorBuilder::prefetched`], cancelled when dropped
+struct Prefetch(tokio::task::JoinHandle<Result<RandomUserResult>>);
+
+impl Prefetch {
+    fn spawn(template: &UserGeneratorBuilder, page: u32, page_size: usize) -> Self {
+        let builder = template.clone().page(page);
+        Self(tokio::spawn(builder.fetch_info_unfiltered(page_size)))
+    }
+}
+
+impl Future for Prefetch {
+    type Output = Result<RandomUserResult>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        Pin::new(&mut self.0)
+            .poll(cx)
+            .map(|joined| joined.unwrap_or_else(|e| std::panic::resume_unwind(e.into_panic())))
+    }
+}
+
+impl Drop for Prefetch {
+    fn drop(&mut self) {
+        self.0.abort();
+    }
+}
+
+struct PageState {
+    builder: UserGeneratorBuilder,
+    seed: String,
+    page_size: usize,
+    page: u32,
+    done: bool,
+}
+
+/// Random user generator
+///
+/// Cloning it is cheap, the clones share the connection pool, the cache and the rate limit.
+///
+/// ## Example:
+/// ```no_run
+/// #[tokio::main]
+/// async fn main() {
+///     use random_user::UserGenerator;
+///
+///     let generator = UserGenerator::new();
+///
+///     let user = generator.fetch_one().await.unwrap();
+///
+///     println!("{:#?}", user);
+/// }
+/// ```
+#[derive(Clone)]
+pub struct UserGenerator {
+    client: reqwest::Client,
+    transport: Arc<dyn UserTransport>,
+    base_url: Url,
+    /// `User-Agent` header of each request, `None` to keep the one of the client
+    user_agent: Option<HeaderValue>,
+    retry: RetryPolicy,
+    /// Timeout of each request, `None` to keep the one of the client
+    timeout: Option<Duration>,
+    nationalities: Vec<Nationality>,
+    password: Option<String>,
+    #[cfg(feature = "offline")]
+    offline_seed: Option<String>,
+}
+
+impl UserGenerator {
+    const API_URL: &str = "https://randomuser.me/api/1.4/";
+    const DEFAULT_TIMEOUT: Duration = Duration::from_secs(30);
+    const DEFAULT_USER_AGENT: &str =
+        concat!(env!("CARGO_PKG_NAME"), "/", env!("CARGO_PKG_VERSION"));
+
+    pub fn new() -> UserGenerator {
+        Self::with_own_client(reqwest::Client::new())
     }
-    /// Request a specific gender
-    pub fn gender(self, gender: EnumSet<Gender>) -> Self {
-        Self::new(self.req, Some(gender))
+
+    /// Generator with the default settings, for a client built by this crate
+    fn with_own_client(client: reqwest::Client) -> UserGenerator {
+        UserGenerator {
+            user_agent: Some(HeaderValue::from_static(Self::DEFAULT_USER_AGENT)),
+            timeout: Some(Self::DEFAULT_TIMEOUT),
+            ..Self::with_client(client)
+        }
     }
 
-    /// Request a specific nationality
-    pub fn nationality(self, nationality: Nationality) -> Self {
-        Self::new(
-            self.req
-                .query(&[("nat", serde_json::to_value(nationality).unwrap().as_str())]),
-            self.gender,
-        )
+    /// Answer requests with canned responses instead of calling the api, for tests
+    ///
+    /// Each request, including retries, gets the next response in order, whatever its filters or count.
+    /// Once all responses are used, requests fail with [`RandomUserError::Api`].
+    ///
+    /// ## Example:
+    /// ```
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     use random_user::{RandomUserInfo, RandomUserResult, UserGenerator};
+    ///
+    ///     let result = RandomUserResult {
+    ///         results: Vec::new(),
+    ///         info: RandomUserInfo {
+    ///             seed: "abc".to_owned(),
+    ///             results: 0,
+    ///             page: 1,
+    ///             version: "1.4".to_owned(),
+    ///         },
+    ///     };
+    ///     let generator = UserGenerator::mock(vec![result.clone()]);
+    ///
+    ///     assert_eq!(generator.fetch_with_info(1).await.unwrap(), result);
+    ///     assert!(generator.fetch_with_info(1).await.is_err());
+    /// }
+    /// ```
+    pub fn mock(responses: Vec<RandomUserResult>) -> UserGenerator {
+        UserGenerator {
+            transport: Arc::new(MockTransport::new(responses)),
+            ..Self::new()
+        }
     }
 
-    /// Request specific nationalities, picked at random between each user
-    pub fn nationalities(self, nationalities: &[Nationality]) -> Self {
-        let mut nats = String::new();
-        for nat in nationalities {
-            nats += serde_json::to_value(nat).unwrap().as_str().unwrap();
-            nats.push(',');
+    /// Use an already configured client, to share its connection pool, proxy, headers, etc.
+    ///
+    /// The timeout and `User-Agent` of the client are kept, unless [`with_timeout`](Self::with_timeout)
+    /// or [`with_user_agent`](Self::with_user_agent) is called.
+    ///
+    /// ## Example:
+    /// ```
+    /// use random_user::UserGenerator;
+    ///
+    /// let client = reqwest::Client::builder()
+    ///     .pool_max_idle_per_host(4)
+    ///     .build()
+    ///     .unwrap();
+    ///
+    /// let generator = UserGenerator::with_client(client);
+    /// ```
+    pub fn with_client(client: reqwest::Client) -> UserGenerator {
+        UserGenerator {
+            transport: Arc::new(HttpTransport::new(client.clone())),
+            client,
+            base_url: Url::parse(Self::API_URL).expect("Default api url is valid"),
+            user_agent: None,
+            retry: RetryPolicy::none(),
+            timeout: None,
+            nationalities: Vec::new(),
+            password: None,
+            #[cfg(feature = "offline")]
+            offline_seed: None,
         }
-        nats.pop();
-        Self::new(self.req.query(&[("nat", nats)]), self.gender)
     }
 
-    /// Request with a specified seed, allow to always generate the same users
+    /// Send all requests through a proxy, for http and https
     ///
-    /// ### Warning:
-    /// May discard other filters
-    pub fn seed(self, seed: &str) -> Self {
-        Self::new(self.req.query(&[("seed", seed)]), self.gender)
+    /// Returns an error if the proxy url is invalid
+    ///
+    /// ## Example:
+    /// ```
+    /// use random_user::UserGenerator;
+    ///
+    /// let generator = UserGenerator::with_proxy("http://proxy.example.com:8080").unwrap();
+    ///
+    /// assert!(UserGenerator::with_proxy("not a url").is_err());
+    /// ```
+    pub fn with_proxy(proxy: &str) -> Result<UserGenerator> {
+        let client = reqwest::Client::builder()
+            .proxy(reqwest::Proxy::all(proxy)?)
+            .build()?;
+        Ok(Self::with_own_client(client))
     }
 
-    /// Request a user with specific password rules
+    /// Configure a generator from environment variables, using the defaults for missing ones
     ///
-    /// Format, without spaces:
-    /// `CHARSETS,MIN_LENGTH-MAX_LENGTH`
-    /// or
-    /// `CHARSETS,MAX_LENGTH`
+    /// - `RANDOM_USER_PROXY`: proxy for all requests, see [`with_proxy`](Self::with_proxy)
+    /// - `RANDOM_USER_BASE_URL`: api base url, see [`with_base_url`](Self::with_base_url)
+    /// - `RANDOM_USER_TIMEOUT`: request timeout in seconds, see [`with_timeout`](Self::with_timeout)
+    /// - `RANDOM_USER_USER_AGENT`: `User-Agent` header, see [`with_user_agent`](Self::with_user_agent)
     ///
-    /// You can mix and match the charsets below for the CHARSETS option above:
-    /// ```md
-    /// special    !"#$%&'()*+,- ./:;<=>?@[\]^_`{|}~
-    /// upper      ABCDEFGHIJKLMNOPQRSTUVWXYZ
-    /// lower      abcdefghijklmnopqrstuvwxyz
-    /// number     0123456789
+    /// Returns an error if a variable has an invalid value
+    ///
+    /// ## Example:
     /// ```
-    /// `MIN_LENGTH` and `MAX_LENGTH` are the min/max length of the passwords that you want to generate.
-    /// By default, passwords will be between 8 - 64 characters long.
+    /// use std::env;
+    /// use random_user::UserGenerator;
+    ///
+    /// env::set_var("RANDOM_USER_BASE_URL", "http://localhost:8080/api/");
+    /// env::set_var("RANDOM_USER_TIMEOUT", "5");
+    /// let generator = UserGenerator::from_env().unwrap();
+    ///
+    /// env::set_var("RANDOM_USER_TIMEOUT", "soon");
+    /// assert!(UserGenerator::from_env().is_err());
+    /// ```
+    pub fn from_env() -> Result<UserGenerator> {
+        let mut generator = match env_var("RANDOM_USER_PROXY")? {
+            Some(proxy) => Self::with_proxy(&proxy)?,
+            None => Self::new(),
+        };
+        if let Some(url) = env_var("RANDOM_USER_BASE_URL")? {
+            generator = generator.with_base_url(url)?;
+        }
+        if let Some(timeout) = env_var("RANDOM_USER_TIMEOUT")? {
+            let seconds = timeout.parse().map_err(|_| RandomUserError::InvalidEnv {
+                name: "RANDOM_USER_TIMEOUT".to_owned(),
+                value: timeout,
+            })?;
+            generator = generator.with_timeout(Duration::from_secs(seconds));
+        }
+        if let Some(user_agent) = env_var("RANDOM_USER_USER_AGENT")? {
+            generator = generator.with_user_agent(&user_agent)?;
+        }
+        Ok(generator)
+    }
+
+    /// Send the requests with another transport, see [`UserTransport`]
+    pub fn with_transport(self, transport: impl UserTransport + 'static) -> Self {
+        Self {
+            transport: Arc::new(transport),
+            ..self
+        }
+    }
+
+    /// Keep the responses of up to `capacity` seeded requests in memory,
+    /// answering identical requests without sending them again
+    ///
+    /// Requests without a seed return new users every time, so they are never cached.
+    /// The cache wraps the current transport, so it must be set after [`with_transport`](Self::with_transport).
     ///
     /// ## Example:
     /// ```
-    /// // Get a user with a password composed with 8 upper and/or lower characters
     /// #[tokio::main]
     /// async fn main() {
-    ///     use random_user::UserGenerator;
-    ///     
-    ///     let generator = UserGenerator::new();
+    ///     use random_user::{RandomUserInfo, RandomUserResult, UserGenerator};
     ///
-    ///     let user = generator.get().password("upper,lower,8").fetch_one().await.unwrap();
+    ///     let result = RandomUserResult {
+    ///         results: Vec::new(),
+    ///         info: RandomUserInfo {
+    ///             seed: "foobar".to_owned(),
+    ///             results: 0,
+    ///             page: 1,
+    ///             version: "1.4".to_owned(),
+    ///         },
+    ///     };
+    ///     // A single response available
+    ///     let generator = UserGenerator::mock(vec![result]).with_cache(16);
+    ///
+    ///     let first = generator.get().seed("foobar").fetch_with_info(5).await.unwrap();
+    ///     // Not sent again
+    ///     let second = generator.get().seed("foobar").fetch_with_info(5).await.unwrap();
+    ///     assert_eq!(first, second);
     /// }
     /// ```
-    pub fn password(self, charset: &str) -> Self {
-        Self::new(self.req.query(&[("password", charset)]), self.gender)
+    pub fn with_cache(self, capacity: usize) -> Self {
+        Self {
+            transport: Arc::new(CachedTransport::new(
+                self.transport.clone(),
+                capacity,
+                false,
+            )),
+            ..self
+        }
     }
 
-    /// Generate users with the api informations
-    pub async fn fetch_with_info(self, count: usize) -> Result<RandomUserResult> {
-        self.count(count).request().await
+    /// Cache seeded requests like [`with_cache`](Self::with_cache), but send them again
+    /// with `If-None-Match` when the cached response has an `ETag`
+    ///
+    /// A `304 Not Modified` answer returns the cached users without downloading them again,
+    /// any other successful answer replaces them.
+    ///
+    /// ## Example:
+    /// ```
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     use futures_util::future::BoxFuture;
+    ///     use random_user::{build_response, RandomUserError, UserGenerator, UserTransport};
+    ///     use reqwest::header::{HeaderMap, HeaderValue, CONTENT_TYPE, ETAG, IF_NONE_MATCH};
+    ///     use reqwest::StatusCode;
+    ///
+    ///     // Answers 304 when the client already has the users
+    ///     struct Tagged;
+    ///
+    ///     impl UserTransport for Tagged {
+    ///         fn send(
+    ///             &self,
+    ///             request: reqwest::Request,
+    ///         ) -> BoxFuture<'_, Result<reqwest::Response, RandomUserError>> {
+    ///             if request.headers().contains_key(IF_NONE_MATCH) {
+    ///                 let response = build_response(StatusCode::NOT_MODIFIED, HeaderMap::new(), "");
+    ///                 return Box::pin(async move { Ok(response) });
+    ///             }
+    ///             let mut headers = HeaderMap::new();
+    ///             headers.insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
+    ///             headers.insert(ETAG, HeaderValue::from_static("\"v1\""));
+    ///             let response = build_response(StatusCode::OK, headers, r#"{"results":[]}"#);
+    ///             Box::pin(async move { Ok(response) })
+    ///         }
+    ///     }
+    ///
+    ///     let generator = UserGenerator::new()
+    ///         .with_transport(Tagged)
+    ///         .with_revalidated_cache(16);
+    ///
+    ///     let first = generator.get().seed("foobar").fetch(5).await.unwrap();
+    ///     let second = generator.get().seed("foobar").fetch(5).await.unwrap();
+    ///     assert_eq!(first, second);
+    /// }
+    /// ```
+    pub fn with_revalidated_cache(self, capacity: usize) -> Self {
+        Self {
+            transport: Arc::new(CachedTransport::new(self.transport.clone(), capacity, true)),
+            ..self
+        }
     }
 
-    /// Generate users
-    pub async fn fetch(self, count: usize) -> Result<Vec<RandomUser>> {
-        Ok(self.count(count).request().await?.results)
+    /// Send at most `requests_per_second` requests, delaying the others,
+    /// to avoid [`RandomUserError::RateLimited`] errors
+    ///
+    /// The limit is shared by all the requests of this generator, including concurrent ones
+    /// and retries. A limit of 0 disables it. Like [`with_cache`](Self::with_cache), it wraps
+    /// the current transport, and cached responses are not delayed if the cache is set after.
+    ///
+    /// ## Example:
+    /// ```
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     use std::time::{Duration, Instant};
+    ///     use random_user::UserGenerator;
+    ///
+    ///     let generator = UserGenerator::mock(Vec::new()).with_rate_limit(20);
+    ///
+    ///     let start = Instant::now();
+    ///     for _ in 0..3 {
+    ///         let _ = generator.get().send_raw().await.unwrap();
+    ///     }
+    ///     assert!(start.elapsed() >= Duration::from_millis(100));
+    /// }
+    /// ```
+    pub fn with_rate_limit(self, requests_per_second: u32) -> Self {
+        if requests_per_second == 0 {
+            return self;
+        }
+        Self {
+            transport: Arc::new(RateLimitedTransport::new(
+                self.transport.clone(),
+                requests_per_second,
+            )),
+            ..self
+        }
     }
 
-    /// Generate 1 user
-    pub async fn fetch_one(self) -> Result<RandomUser> {
-        Ok(self.fetch(1).await?.remove(0))
+    /// Use another api base url, like a self-hosted mirror or a mock server
+    ///
+    /// Returns an error if the url can't be parsed
+    ///
+    /// ## Example:
+    /// ```
+    /// use random_user::UserGenerator;
+    ///
+    /// let generator = UserGenerator::new()
+    ///     .with_base_url("http://localhost:3000/api/")
+    ///     .unwrap();
+    ///
+    /// assert!(UserGenerator::new().with_base_url("not an url").is_err());
+    /// ```
+    pub fn with_base_url(self, url: impl Into<String>) -> Result<Self> {
+        let base_url = Url::parse(&url.into())?;
+        Ok(Self { base_url, ..self })
     }
 
-    fn count(self, count: usize) -> Self {
-        Self::new(self.req.query(&[("results", count)]), self.gender)
+    /// Use another version of the api, 1.4 by default
+    ///
+    /// The version replaces the last segment of the base url if it is a version,
+    /// or is appended to it otherwise.
+    /// Returns an error if the version is not formatted like `1.4`
+    ///
+    /// ## Example:
+    /// ```
+    /// use random_user::UserGenerator;
+    ///
+    /// let generator = UserGenerator::new().with_version("1.3").unwrap();
+    ///
+    /// assert!(UserGenerator::new().with_version("latest").is_err());
+    /// ```
+    pub fn with_version(self, version: &str) -> Result<Self> {
+        if !is_version(version) {
+            return Err(RandomUserError::InvalidVersion(version.to_owned()));
+        }
+        let mut base_url = self.base_url;
+        let mut segments: Vec<String> = base_url
+            .path_segments()
+            .map(|segments| {
+                segments
+                    .filter(|segment| !segment.is_empty())
+                    .map(str::to_owned)
+                    .collect()
+            })
+            .unwrap_or_default();
+        if segments.last().is_some_and(|segment| is_version(segment)) {
+            segments.pop();
+        }
+        segments.push(version.to_owned());
+        base_url.set_path(&format!("{}/", segments.join("/")));
+        Ok(Self { base_url, ..self })
     }
 
-    async fn request(self) -> Result<RandomUserResult> {
-        let api_rsp = self.req.send().await?;
-        let rsp = Self::parse_response(api_rsp).await?;
-        match rsp {
-            RandomUserResponse::Error(e) => Err(RandomUserError::Api(e)),
-            RandomUserResponse::Result(res) => Ok(RandomUserResult {
-                results: {
-                    res.results
-                        .iter()
-                        .map(move |user| {
-                            let gender = self.gender.unwrap_or_else(Gender::random_gender);
-                            let mut user = user.to_owned();
-                            user.gender = gender;
-                            user
-                        })
-                        .collect()
-                },
-                info: res.info,
-            }),
-        }
+    /// Retry failed requests according to the given policy
+    ///
+    /// ## Example:
+    /// ```
+    /// use std::time::Duration;
+    /// use random_user::{RetryPolicy, UserGenerator};
+    ///
+    /// // Try up to 5 times, waiting 100ms, 200ms, 400ms then 800ms between attempts
+    /// let generator = UserGenerator::new()
+    ///     .with_retry(RetryPolicy::new(5, Duration::from_millis(100)));
+    /// ```
+    pub fn with_retry(self, retry: RetryPolicy) -> Self {
+        Self { retry, ..self }
     }
 
-    async fn parse_response(response: reqwest::Response) -> Result<RandomUserResponse> {
-        let content_type = response
-            .headers()
-            .get("content-type")
-            .ok_or(RandomUserError::BadFormat)?
-            .to_str()
-            .map_err(|_| RandomUserError::BadFormat)?
-            .to_owned();
-        let text = response.text().await?;
-        match content_type {
-            ct if ct.contains("text/plain") => Ok(RandomUserResponse::Error(text)),
-            ct if ct.contains("application/json") => {
-                serde_json::from_str::<RandomUserResponse>(&text)
-                    .map_err(|_| RandomUserError::BadFormat)
-            }
-            _ => Err(RandomUserError::BadFormat),
+    /// Give up on requests taking longer than the given duration, 30 seconds by default
+    ///
+    /// A generator created with [`with_client`](Self::with_client) keeps the timeout
+    /// of the client by default, if any.
+    ///
+    /// An expired request fails with [`RandomUserError::Timeout`]
+    ///
+    /// ## Example:
+    /// ```
+    /// use std::time::Duration;
+    /// use random_user::UserGenerator;
+    ///
+    /// let generator = UserGenerator::new().with_timeout(Duration::from_secs(5));
+    /// ```
+    pub fn with_timeout(self, timeout: Duration) -> Self {
+        Self {
+            timeout: Some(timeout),
+            ..self
         }
     }
-}
 
-/// Random user generator
-///
-/// ## Example:
-/// ```
-/// #[tokio::main]
-/// async fn main() {
-///     use random_user::UserGenerator;
-///
-///     let generator = UserGenerator::new();
-///
-///     let user = generator.fetch_one().await.unwrap();
-///
-///     println!("{:#?}", user);
-/// }
-/// ```
-pub struct UserGenerator {
-    client: reqwest::Client,
-}
+    /// Send the given `User-Agent` header, `random_user/<version>` by default
+    ///
+    /// A generator created with [`with_client`](Self::with_client) keeps the `User-Agent`
+    /// of the client by default, if any.
+    ///
+    /// ## Example:
+    /// ```
+    /// use random_user::UserGenerator;
+    ///
+    /// let generator = UserGenerator::new().with_user_agent("my-app/1.0").unwrap();
+    ///
+    /// assert!(UserGenerator::new().with_user_agent("bad\nagent").is_err());
+    /// ```
+    pub fn with_user_agent(self, user_agent: &str) -> Result<Self> {
+        Ok(Self {
+            user_agent: Some(HeaderValue::from_str(user_agent)?),
+            ..self
+        })
+    }
 
-impl UserGenerator {
-    const API_URL: &str = "https://randomuser.me/api/1.4/";
+    /// Request this nationality by default, unless a request sets its own nationalities
+    ///
+    /// ## Example:
+    /// ```
+    /// use random_user::{Nationality, UserGenerator};
+    ///
+    /// let generator = UserGenerator::new()
+    ///     .with_default_nationality(Nationality::French)
+    ///     .with_default_password("upper,lower,12")
+    ///     .unwrap();
+    ///
+    /// let url = generator.get().build_url().unwrap();
+    /// assert_eq!(url.query(), Some("password=upper%2Clower%2C12&nat=FR"));
+    ///
+    /// let url = generator
+    ///     .get()
+    ///     .nationality(Nationality::Swiss)
+    ///     .password("number,4")
+    ///     .build_url()
+    ///     .unwrap();
+    /// assert_eq!(url.query(), Some("password=number%2C4&nat=CH"));
+    /// ```
+    pub fn with_default_nationality(self, nationality: Nationality) -> Self {
+        Self {
+            nationalities: vec![nationality],
+            ..self
+        }
+    }
 
-    pub fn new() -> UserGenerator {
-        UserGenerator {
-            client: reqwest::Client::new(),
+    /// Request passwords following these rules by default, unless a request sets its own
+    ///
+    /// Returns an error if the rules are invalid, see [`PasswordSpec`] for the format.
+    pub fn with_default_password(self, charset: &str) -> Result<Self> {
+        charset.parse::<PasswordSpec>()?;
+        Ok(Self {
+            password: Some(charset.to_owned()),
+            ..self
+        })
+    }
+
+    /// Generate users locally when the api can't be reached, requires the `offline` feature
+    ///
+    /// On a connection error or a timeout, [`fetch`](UserGeneratorBuilder::fetch) and
+    /// [`fetch_with_info`](UserGeneratorBuilder::fetch_with_info) return plausible fake users,
+    /// always the same ones for a given seed, the one of the request if any, and page.
+    /// Gender and nationality filters are followed, the others are ignored.
+    ///
+    /// ## Example:
+    /// ```
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     use random_user::{Nationality, UserGenerator};
+    ///
+    ///     // Nothing listens on this port
+    ///     let generator = UserGenerator::new()
+    ///         .with_base_url("http://127.0.0.1:9/api/")
+    ///         .unwrap()
+    ///         .with_offline_fallback("foobar");
+    ///
+    ///     let users = generator.get().nationality(Nationality::Irish).fetch(5).await.unwrap();
+    ///     assert_eq!(users.len(), 5);
+    ///     assert!(users.iter().all(|user| user.nationality == Nationality::Irish));
+    ///
+    ///     let same_users = generator.get().nationality(Nationality::Irish).fetch(5).await.unwrap();
+    ///     assert_eq!(users, same_users);
+    ///
+    ///     let next_users = generator.get().nationality(Nationality::Irish).page(2).fetch(5).await.unwrap();
+    ///     assert_ne!(users, next_users);
+    /// }
+    /// ```
+    #[cfg(feature = "offline")]
+    pub fn with_offline_fallback(self, seed: &str) -> Self {
+        Self {
+            offline_seed: Some(seed.to_owned()),
+            ..self
         }
     }
 
     /// Start the request to easily apply filters
     pub fn get(&self) -> UserGeneratorBuilder {
-        UserGeneratorBuilder::new(self.client.get(Self::API_URL), None)
+        UserGeneratorBuilder::new(self)
+    }
+
+    /// Walk the pages of a seeded dataset one at a time, see [`Cursor`]
+    pub fn cursor(&self, seed: &str, page_size: usize) -> Cursor {
+        self.get().cursor(seed, page_size)
+    }
+
+    /// Generate users of a seeded dataset lazily, fetching one page ahead,
+    /// see [`UserGeneratorBuilder::prefetched`]
+    pub fn iter(&self, seed: &str, page_size: usize) -> impl Stream<Item = Result<RandomUser>> {
+        self.get().prefetched(seed, page_size)
     }
 
     /// Generate users with the api informations
     ///
     /// ## Example:
-    /// ```
+    /// ```no_run
     /// // Fetch 5 random users with api info
     /// #[tokio::main]
     /// async fn main() {
@@ -185,7 +2351,7 @@ impl UserGenerator {
     ///     let users = generator.fetch_with_info(5).await.unwrap();
     ///
     ///     println!("{:?}", users.info);
-    ///     for user in users.results {
+    ///     for user in users {
     ///         println!("{user:?}");
     ///     };
     /// }
@@ -197,7 +2363,7 @@ impl UserGenerator {
     /// Generate users
     ///
     /// ## Example:
-    /// ```
+    /// ```no_run
     /// // Fetch 5 random users
     /// #[tokio::main]
     /// async fn main() {
@@ -219,7 +2385,7 @@ impl UserGenerator {
     /// Generate a user
     ///
     /// ## Example:
-    /// ```
+    /// ```no_run
     /// #[tokio::main]
     /// async fn main() {
     ///     use random_user::UserGenerator;
@@ -233,6 +2399,27 @@ impl UserGenerator {
     pub async fn fetch_one(&self) -> Result<RandomUser> {
         self.get().fetch_one().await
     }
+
+    /// Generate a user from a seed, the same seed always giving the same user
+    ///
+    /// The seed is url encoded, so it can contain any character
+    ///
+    /// ## Example:
+    /// ```no_run
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     use random_user::UserGenerator;
+    ///
+    ///     let generator = UserGenerator::new();
+    ///
+    ///     let user = generator.fetch_seeded("user #42").await.unwrap();
+    ///     let same_user = generator.fetch_seeded("user #42").await.unwrap();
+    ///     assert_eq!(user, same_user);
+    /// }
+    /// ```
+    pub async fn fetch_seeded(&self, seed: &str) -> Result<RandomUser> {
+        self.get().seed(seed).fetch_one().await
+    }
 }
 
 impl Default for UserGenerator {
@@ -246,9 +2433,120 @@ type Result<T> = std::result::Result<T, RandomUserError>;
 #[derive(Debug, Error)]
 pub enum RandomUserError {
     #[error("Reqwest error: {0}")]
-    Reqwest(#[from] reqwest::Error),
+    Reqwest(#[source] reqwest::Error),
+    #[error("Request timed out")]
+    Timeout,
     #[error("Api error: {0}")]
     Api(String),
+    #[error("Rate limited, retry after {retry_after:?}")]
+    RateLimited { retry_after: Option<Duration> },
+    #[error("Http error: {status}")]
+    Http { status: StatusCode, body: String },
     #[error("Bad format")]
     BadFormat,
+    #[error("Parse error: {source}")]
+    Parse {
+        source: serde_json::Error,
+        body: String,
+    },
+    #[error("Io error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Invalid url: {0}")]
+    InvalidUrl(#[from] url::ParseError),
+    #[error("No user returned")]
+    EmptyResult,
+    #[error("Only {found} users out of {requested} matched")]
+    NotEnoughUsers { requested: usize, found: usize },
+    #[error("Invalid count: {0}, must be between 1 and 5000")]
+    InvalidCount(usize),
+    #[error("Invalid age range: {min}-{max}")]
+    InvalidAgeRange { min: u8, max: u8 },
+    #[error("Invalid weights, they must not all be 0")]
+    InvalidWeights,
+    #[error("Invalid timezone offset: {0}")]
+    InvalidOffset(String),
+    #[error("Invalid hex hash: {0}")]
+    InvalidHash(String),
+    #[error("Invalid header value: {0}")]
+    InvalidHeader(#[from] reqwest::header::InvalidHeaderValue),
+    #[error("Invalid api version: {0}")]
+    InvalidVersion(String),
+    #[error("Invalid environment variable {name}: {value}")]
+    InvalidEnv { name: String, value: String },
+    #[error("Invalid password spec: {0}")]
+    PasswordSpec(#[from] PasswordSpecError),
+}
+
+/// Predicates to check the kind of error, mostly useful in tests
+///
+/// ## Example:
+/// ```
+/// #[tokio::main]
+/// async fn main() {
+///     use random_user::UserGenerator;
+///
+///     let err = UserGenerator::new().fetch(0).await.unwrap_err();
+///     assert!(err.is_invalid_input());
+///     assert!(!err.is_rate_limited());
+/// }
+/// ```
+impl RandomUserError {
+    pub fn is_timeout(&self) -> bool {
+        matches!(self, RandomUserError::Timeout)
+    }
+
+    pub fn is_rate_limited(&self) -> bool {
+        matches!(self, RandomUserError::RateLimited { .. })
+    }
+
+    /// The api answered with an error message
+    pub fn is_api(&self) -> bool {
+        matches!(self, RandomUserError::Api(_))
+    }
+
+    pub fn is_bad_format(&self) -> bool {
+        matches!(self, RandomUserError::BadFormat)
+    }
+
+    pub fn is_parse(&self) -> bool {
+        matches!(self, RandomUserError::Parse { .. })
+    }
+
+    /// An argument was rejected before sending any request
+    ///
+    /// Invalid data returned by the api, like [`InvalidOffset`](Self::InvalidOffset)
+    /// or [`InvalidHash`](Self::InvalidHash), is not an invalid input.
+    pub fn is_invalid_input(&self) -> bool {
+        matches!(
+            self,
+            RandomUserError::InvalidUrl(_)
+                | RandomUserError::InvalidCount(_)
+                | RandomUserError::InvalidAgeRange { .. }
+                | RandomUserError::InvalidWeights
+                | RandomUserError::InvalidHeader(_)
+                | RandomUserError::InvalidEnv { .. }
+                | RandomUserError::InvalidVersion(_)
+                | RandomUserError::PasswordSpec(_)
+        )
+    }
+
+    /// Status of the response, for http errors and rate limiting
+    pub fn status(&self) -> Option<StatusCode> {
+        match self {
+            RandomUserError::Http { status, .. } => Some(*status),
+            RandomUserError::RateLimited { .. } => Some(StatusCode::TOO_MANY_REQUESTS),
+            RandomUserError::Reqwest(e) => e.status(),
+            _ => None,
+        }
+    }
+}
+
+impl From<reqwest::Error> for RandomUserError {
+    fn from(error: reqwest::Error) -> Self {
+        if error.is_timeout() {
+            RandomUserError::Timeout
+        } else {
+            RandomUserError::Reqwest(error)
+        }
+    }
 }
@@ -6,7 +6,7 @@
 //! ## Examples
 //!
 //! ### Generate one random user:
-//! ```
+//! ```no_run
 //! use random_user::UserGenerator;
 //!
 //! #[tokio::main]
@@ -23,7 +23,7 @@
 //! ```
 //!
 //! ### Generate multiple random users with filters:
-//! ```
+//! ```no_run
 //! use random_user::{Nationality, Gender, UserGenerator};
 //! #[tokio::main]
 //! async fn main() {
@@ -40,8 +40,20 @@
 //! }
 //! ```
 
+mod cache;
+mod cursor;
 mod generator;
+#[cfg(feature = "offline")]
+mod offline;
+mod password;
+mod rate_limit;
+mod retry;
+mod transport;
 mod types;
 
-pub use generator::UserGenerator;
+pub use cursor::Cursor;
+pub use generator::{RandomUserError, UserGenerator, UserGeneratorBuilder};
+pub use password::{generate_password, Charset, PasswordSpec, PasswordSpecError};
+pub use retry::RetryPolicy;
+pub use transport::{build_response, json_response, HttpTransport, UserTransport};
 pub use types::*;
@@ -0,0 +1,249 @@
+use crate::generator::RandomUserError;
+use crate::types::*;
+use chrono::{DateTime, TimeZone, Utc};
+use rand::distributions::Alphanumeric;
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+
+const FEMALE_NAMES: &[&str] = &[
+    "Alice", "Chloe", "Emma", "Hannah", "Isabel", "Julia", "Laura", "Lea", "Lucy", "Maria", "Nina",
+    "Olivia", "Sara", "Sofia", "Zoe",
+];
+const MALE_NAMES: &[&str] = &[
+    "Adam", "Daniel", "David", "Felix", "Hugo", "Jack", "Leo", "Lucas", "Max", "Noah", "Oscar",
+    "Paul", "Samuel", "Thomas", "Victor",
+];
+const LAST_NAMES: &[&str] = &[
+    "Brown", "Garcia", "Hansen", "Jensen", "Johnson", "Martin", "Miller", "Moreau", "Muller",
+    "Nielsen", "Petit", "Rossi", "Schmidt", "Smith", "Walker",
+];
+const STREETS: &[&str] = &[
+    "Church Street",
+    "High Street",
+    "Lake Road",
+    "Main Street",
+    "Mill Lane",
+    "Park Avenue",
+    "Station Road",
+    "Victoria Road",
+];
+const CITIES: &[&str] = &[
+    "Ashford",
+    "Bridgeport",
+    "Fairview",
+    "Georgetown",
+    "Kingston",
+    "Newport",
+    "Riverside",
+    "Springfield",
+];
+const STATES: &[&str] = &["Central", "Eastern", "Northern", "Southern", "Western"];
+const USERNAME_WORDS: &[&str] = &[
+    "bird", "cat", "dog", "fish", "frog", "lion", "rabbit", "tiger", "wolf",
+];
+const USERNAME_ADJECTIVES: &[&str] = &[
+    "black", "blue", "green", "happy", "lazy", "red", "silver", "tiny", "yellow",
+];
+const TIMEZONES: &[(&str, &str)] = &[
+    ("-5:00", "Eastern Time (US & Canada), Bogota, Lima"),
+    ("0:00", "Western Europe Time, London, Lisbon, Casablanca"),
+    ("+1:00", "Brussels, Copenhagen, Madrid, Paris"),
+    ("+5:30", "Bombay, Calcutta, Madras, New Delhi"),
+    ("+10:00", "Eastern Australia, Guam, Vladivostok"),
+];
+
+/// Generate plausible users locally, always the same ones for a given seed and page
+///
+/// Hashes and pictures only look like the api ones, they are not derived from the login.
+pub(crate) fn users(
+    seed: &str,
+    page: u32,
+    count: usize,
+    gender: Option<&Gender>,
+    nationalities: &[Nationality],
+) -> Vec<RandomUser> {
+    let mut rng = StdRng::seed_from_u64(fnv1a(seed) ^ u64::from(page));
+    (0..count)
+        .map(|_| user(&mut rng, gender, nationalities))
+        .collect()
+}
+
+fn user(rng: &mut StdRng, gender: Option<&Gender>, nationalities: &[Nationality]) -> RandomUser {
+    let gender = match gender {
+        Some(gender) => gender.clone(),
+        None if rng.gen() => Gender::Female,
+        None => Gender::Male,
+    };
+    let (title, first_names, portraits) = match gender {
+        Gender::Female => ("Ms", FEMALE_NAMES, "women"),
+        Gender::Male => ("Mr", MALE_NAMES, "men"),
+        Gender::Other(_) => ("Mx", FEMALE_NAMES, "women"),
+    };
+    let name = Name {
+        title: title.to_owned(),
+        first: pick(rng, first_names).to_string(),
+        last: pick(rng, LAST_NAMES).to_string(),
+    };
+    let nationality = *nationalities
+        .choose(rng)
+        .unwrap_or_else(|| pick(rng, Nationality::all()));
+    let username = format!(
+        "{}{}{}",
+        pick(rng, USERNAME_ADJECTIVES),
+        pick(rng, USERNAME_WORDS),
+        rng.gen_range(100..1000)
+    );
+    let (offset, description) = *pick(rng, TIMEZONES);
+    let birthday = random_date(rng, 1945, 2005);
+    let registered = random_date(rng, 2002, 2023);
+    let picture = rng.gen_range(0..100);
+    RandomUser {
+        email: format!("{}.{}@example.com", name.first, name.last).to_lowercase(),
+        name,
+        gender,
+        location: Location {
+            street: Street {
+                number: rng.gen_range(1..10000),
+                name: pick(rng, STREETS).to_string(),
+            },
+            city: pick(rng, CITIES).to_string(),
+            state: pick(rng, STATES).to_string(),
+            country: nationality.country_name().to_owned(),
+            postcode: Postcode::Numeric(rng.gen_range(10000..100000)),
+            coordinates: Coordinates {
+                latitude: format!("{:.4}", rng.gen_range(-90.0..90.0)),
+                longitude: format!("{:.4}", rng.gen_range(-180.0..180.0)),
+            },
+            timezone: Timezone {
+                offset: offset.to_owned(),
+                description: description.to_owned(),
+            },
+        },
+        login: Login {
+            uuid: format!(
+                "{}-{}-{}-{}-{}",
+                hex(rng, 8),
+                hex(rng, 4),
+                hex(rng, 4),
+                hex(rng, 4),
+                hex(rng, 12)
+            ),
+            username,
+            password: alphanumeric(rng, 10),
+            salt: alphanumeric(rng, 8),
+            md5: hex(rng, 32),
+            sha1: hex(rng, 40),
+            sha256: hex(rng, 64),
+        },
+        birthday,
+        registered,
+        phone: PhoneNumber(phone(rng)),
+        cell: PhoneNumber(phone(rng)),
+        id: Identity {
+            name: String::new(),
+            value: None,
+        },
+        picture: Picture {
+            large: format!("https://randomuser.me/api/portraits/{portraits}/{picture}.jpg"),
+            medium: format!("https://randomuser.me/api/portraits/med/{portraits}/{picture}.jpg"),
+            thumbnail: format!(
+                "https://randomuser.me/api/portraits/thumb/{portraits}/{picture}.jpg"
+            ),
+        },
+        nationality,
+    }
+}
+
+fn pick<'a, T>(rng: &mut StdRng, items: &'a [T]) -> &'a T {
+    items.choose(rng).expect("Offline lists are not empty")
+}
+
+fn random_date(rng: &mut StdRng, from_year: i32, to_year: i32) -> RandomDate {
+    let from = year_start(from_year).timestamp();
+    let to = year_start(to_year).timestamp();
+    let date = Utc
+        .timestamp_opt(rng.gen_range(from..to), 0)
+        .single()
+        .expect("Offline dates are in range");
+    RandomDate {
+        age: RandomDate { date, age: 0 }.age_now() as i32,
+        date,
+    }
+}
+
+fn year_start(year: i32) -> DateTime<Utc> {
+    Utc.with_ymd_and_hms(year, 1, 1, 0, 0, 0)
+        .single()
+        .expect("Offline dates are in range")
+}
+
+fn phone(rng: &mut StdRng) -> String {
+    format!(
+        "({:03})-{:03}-{:04}",
+        rng.gen_range(200..1000),
+        rng.gen_range(0..1000),
+        rng.gen_range(0..10000)
+    )
+}
+
+fn hex(rng: &mut StdRng, len: usize) -> String {
+    (0..len)
+        .map(|_| char::from_digit(rng.gen_range(0..16), 16).expect("Digit is below 16"))
+        .collect()
+}
+
+fn alphanumeric(rng: &mut StdRng, len: usize) -> String {
+    rng.sample_iter(Alphanumeric)
+        .take(len)
+        .map(char::from)
+        .collect()
+}
+
+/// Stable hash of the seed, unlike the std hasher which may change between releases
+fn fnv1a(seed: &str) -> u64 {
+    seed.bytes().fold(0xcbf29ce484222325, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x100000001b3)
+    })
+}
+
+/// Filters of a request, to generate users locally if the api can't be reached
+pub(crate) struct Fallback {
+    pub(crate) seed: String,
+    pub(crate) page: u32,
+    pub(crate) gender: Option<Gender>,
+    pub(crate) nationalities: Vec<Nationality>,
+}
+
+impl Fallback {
+    /// Whether the error means the api could not be reached at all
+    pub(crate) fn applies(error: &RandomUserError) -> bool {
+        match error {
+            RandomUserError::Reqwest(e) => e.is_connect(),
+            RandomUserError::Timeout => true,
+            _ => false,
+        }
+    }
+
+    pub(crate) fn users(&self, count: usize) -> Vec<RandomUser> {
+        users(
+            &self.seed,
+            self.page,
+            count,
+            self.gender.as_ref(),
+            &self.nationalities,
+        )
+    }
+
+    pub(crate) fn result(&self, count: usize) -> RandomUserResult {
+        RandomUserResult {
+            results: self.users(count),
+            info: RandomUserInfo {
+                seed: self.seed.clone(),
+                results: count as i32,
+                page: self.page as i32,
+                version: "offline".to_owned(),
+            },
+        }
+    }
+}
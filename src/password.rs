@@ -0,0 +1,253 @@
+use enumset::{EnumSet, EnumSetType};
+use rand::seq::SliceRandom;
+use rand::Rng;
+use std::fmt;
+use std::str::FromStr;
+use thiserror::Error;
+
+/// Set of characters a password can be made of
+#[derive(Debug, EnumSetType)]
+pub enum Charset {
+    Special,
+    Upper,
+    Lower,
+    Number,
+}
+
+impl Charset {
+    /// Name of the charset in a password spec
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Charset::Special => "special",
+            Charset::Upper => "upper",
+            Charset::Lower => "lower",
+            Charset::Number => "number",
+        }
+    }
+
+    /// Characters of the charset
+    pub fn chars(&self) -> &'static str {
+        match self {
+            Charset::Special => r##" !"#$%&'()*+,-./:;<=>?@[\]^_`{|}~"##,
+            Charset::Upper => "ABCDEFGHIJKLMNOPQRSTUVWXYZ",
+            Charset::Lower => "abcdefghijklmnopqrstuvwxyz",
+            Charset::Number => "0123456789",
+        }
+    }
+}
+
+/// Password rules, using the same syntax as the api
+///
+/// Format, without spaces:
+/// `CHARSETS,MIN_LENGTH-MAX_LENGTH`
+/// or
+/// `CHARSETS,LENGTH`
+///
+/// `CHARSETS` is a comma separated list of `special`, `upper`, `lower` and `number`.
+/// When the length is omitted, passwords will be between 8 - 64 characters long.
+///
+/// ## Example:
+/// ```
+/// use random_user::{Charset, PasswordSpec};
+///
+/// let spec: PasswordSpec = "upper,lower,12-24".parse().unwrap();
+/// assert_eq!(spec.charsets(), Charset::Upper | Charset::Lower);
+/// assert_eq!(spec.min_length(), 12);
+/// assert_eq!(spec.max_length(), 24);
+/// assert_eq!(spec.to_string(), "upper,lower,12-24");
+///
+/// assert!("upper,lower,50-10".parse::<PasswordSpec>().is_err());
+/// assert!("uper,8".parse::<PasswordSpec>().is_err());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PasswordSpec {
+    charsets: EnumSet<Charset>,
+    min_length: usize,
+    max_length: usize,
+}
+
+impl PasswordSpec {
+    const DEFAULT_MIN_LENGTH: usize = 8;
+    const DEFAULT_MAX_LENGTH: usize = 64;
+    /// Longest passwords that [`with_min_entropy`](Self::with_min_entropy) asks for
+    const MAX_ENTROPY_LENGTH: usize = 128;
+
+    /// Create password rules, checking that there is at least one charset
+    /// and that the length range is valid
+    pub fn new(
+        charsets: EnumSet<Charset>,
+        min_length: usize,
+        max_length: usize,
+    ) -> Result<Self, PasswordSpecError> {
+        if charsets.is_empty() {
+            return Err(PasswordSpecError::NoCharset);
+        }
+        if min_length == 0 || min_length > max_length {
+            return Err(PasswordSpecError::InvalidRange {
+                min: min_length,
+                max: max_length,
+            });
+        }
+        Ok(Self {
+            charsets,
+            min_length,
+            max_length,
+        })
+    }
+
+    /// Password rules using all the charsets, with passwords long enough
+    /// to have at least `bits` of entropy
+    ///
+    /// The entropy is approximated as `length * log2(charset size)`, assuming each character
+    /// is picked uniformly among all the charsets, which is how they are generated.
+    /// Passwords are at least 1 character, and up to 64 if that is enough.
+    /// Returns an error if `bits` is negative, infinite or NaN, or if it needs passwords
+    /// longer than 128 characters, which is about 840 bits.
+    ///
+    /// ## Example:
+    /// ```
+    /// use enumset::EnumSet;
+    /// use random_user::{PasswordSpec, PasswordSpecError};
+    ///
+    /// // 95 characters give about 6.57 bits each
+    /// let spec = PasswordSpec::with_min_entropy(60.0).unwrap();
+    /// assert_eq!(spec.charsets(), EnumSet::all());
+    /// assert_eq!(spec.min_length(), 10);
+    /// assert_eq!(spec.to_string(), "special,upper,lower,number,10-64");
+    ///
+    /// assert!(matches!(
+    ///     PasswordSpec::with_min_entropy(f64::INFINITY),
+    ///     Err(PasswordSpecError::InvalidEntropy(_))
+    /// ));
+    /// assert!(PasswordSpec::with_min_entropy(f64::NAN).is_err());
+    /// assert!(PasswordSpec::with_min_entropy(-1.0).is_err());
+    /// assert!(PasswordSpec::with_min_entropy(1e30).is_err());
+    /// assert_eq!(PasswordSpec::with_min_entropy(800.0).unwrap().max_length(), 122);
+    /// ```
+    pub fn with_min_entropy(bits: f64) -> Result<Self, PasswordSpecError> {
+        if !bits.is_finite() || bits < 0.0 {
+            return Err(PasswordSpecError::InvalidEntropy(bits));
+        }
+        let charsets: EnumSet<Charset> = EnumSet::all();
+        let size: usize = charsets
+            .iter()
+            .map(|charset| charset.chars().chars().count())
+            .sum();
+        let length = (bits / (size as f64).log2()).ceil();
+        if length > Self::MAX_ENTROPY_LENGTH as f64 {
+            return Err(PasswordSpecError::InvalidEntropy(bits));
+        }
+        let length = (length as usize).max(1);
+        Ok(Self {
+            charsets,
+            min_length: length,
+            max_length: length.max(Self::DEFAULT_MAX_LENGTH),
+        })
+    }
+
+    pub fn charsets(&self) -> EnumSet<Charset> {
+        self.charsets
+    }
+
+    pub fn min_length(&self) -> usize {
+        self.min_length
+    }
+
+    pub fn max_length(&self) -> usize {
+        self.max_length
+    }
+
+    /// Generate a password following these rules
+    pub fn generate(&self) -> String {
+        let chars: Vec<char> = self
+            .charsets
+            .iter()
+            .flat_map(|charset| charset.chars().chars())
+            .collect();
+        let mut rng = rand::thread_rng();
+        let length = rng.gen_range(self.min_length..=self.max_length);
+        (0..length).filter_map(|_| chars.choose(&mut rng)).collect()
+    }
+}
+
+impl FromStr for PasswordSpec {
+    type Err = PasswordSpecError;
+
+    fn from_str(spec: &str) -> Result<Self, Self::Err> {
+        let mut charsets = EnumSet::empty();
+        let mut length = None;
+        for token in spec.split(',') {
+            if length.is_some() {
+                return Err(PasswordSpecError::InvalidLength(token.to_owned()));
+            }
+            match token {
+                "special" => charsets |= Charset::Special,
+                "upper" => charsets |= Charset::Upper,
+                "lower" => charsets |= Charset::Lower,
+                "number" => charsets |= Charset::Number,
+                token if token.starts_with(|c: char| c.is_ascii_digit()) => {
+                    length = Some(parse_length(token)?)
+                }
+                token => return Err(PasswordSpecError::UnknownCharset(token.to_owned())),
+            }
+        }
+        let (min, max) = length.unwrap_or((Self::DEFAULT_MIN_LENGTH, Self::DEFAULT_MAX_LENGTH));
+        Self::new(charsets, min, max)
+    }
+}
+
+impl fmt::Display for PasswordSpec {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for charset in self.charsets {
+            write!(f, "{},", charset.as_str())?;
+        }
+        if self.min_length == self.max_length {
+            write!(f, "{}", self.max_length)
+        } else {
+            write!(f, "{}-{}", self.min_length, self.max_length)
+        }
+    }
+}
+
+fn parse_length(token: &str) -> Result<(usize, usize), PasswordSpecError> {
+    let invalid = || PasswordSpecError::InvalidLength(token.to_owned());
+    match token.split_once('-') {
+        Some((min, max)) => Ok((
+            min.parse().map_err(|_| invalid())?,
+            max.parse().map_err(|_| invalid())?,
+        )),
+        None => {
+            let length = token.parse().map_err(|_| invalid())?;
+            Ok((length, length))
+        }
+    }
+}
+
+/// Generate a password locally, following rules with the same syntax as the api
+///
+/// See [`PasswordSpec`] for the format of the rules.
+///
+/// ## Example:
+/// ```
+/// let password = random_user::generate_password("upper,number,8").unwrap();
+///
+/// assert_eq!(password.len(), 8);
+/// assert!(password.chars().all(|c| c.is_ascii_uppercase() || c.is_ascii_digit()));
+/// ```
+pub fn generate_password(spec: &str) -> Result<String, PasswordSpecError> {
+    Ok(spec.parse::<PasswordSpec>()?.generate())
+}
+
+#[derive(Debug, Error, PartialEq)]
+pub enum PasswordSpecError {
+    #[error("Unknown charset: {0}")]
+    UnknownCharset(String),
+    #[error("Invalid length: {0}")]
+    InvalidLength(String),
+    #[error("Invalid length range: {min}-{max}")]
+    InvalidRange { min: usize, max: usize },
+    #[error("No charset")]
+    NoCharset,
+    #[error("Invalid entropy: {0} bits")]
+    InvalidEntropy(f64),
+}
@@ -0,0 +1,49 @@
+use crate::generator::RandomUserError;
+use crate::transport::UserTransport;
+use futures_util::future::BoxFuture;
+use rand::Rng;
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::{Duration, Instant};
+
+/// Transport spacing out the requests sent through it, whatever the builder sending them
+pub(crate) struct RateLimitedTransport {
+    inner: Arc<dyn UserTransport>,
+    interval: Duration,
+    next: Mutex<Instant>,
+}
+
+impl RateLimitedTransport {
+    pub(crate) fn new(inner: Arc<dyn UserTransport>, requests_per_second: u32) -> Self {
+        Self {
+            inner,
+            interval: Duration::from_secs(1) / requests_per_second,
+            next: Mutex::new(Instant::now()),
+        }
+    }
+
+    /// Book the next free slot, returning how long to wait for it
+    fn reserve(&self) -> Duration {
+        let mut next = self.next.lock().unwrap_or_else(PoisonError::into_inner);
+        let now = Instant::now();
+        let slot = (*next).max(now);
+        *next = slot + self.interval;
+        slot - now
+    }
+}
+
+impl UserTransport for RateLimitedTransport {
+    fn send(
+        &self,
+        request: reqwest::Request,
+    ) -> BoxFuture<'_, Result<reqwest::Response, RandomUserError>> {
+        Box::pin(async move {
+            let delay = self.reserve();
+            if !delay.is_zero() {
+                // Jitter so that requests waiting together don't hit the api at the same instant
+                let jitter = rand::thread_rng().gen_range(Duration::ZERO..=self.interval / 10);
+                tokio::time::sleep(delay + jitter).await;
+            }
+            self.inner.send(request).await
+        })
+    }
+}
@@ -0,0 +1,87 @@
+use crate::generator::RandomUserError;
+use std::time::Duration;
+
+/// Policy to retry failed requests, with an exponential backoff between attempts
+///
+/// By default, timeouts, connection errors, server errors and rate limiting are retried,
+/// honoring the `Retry-After` header when the api sends one.
+///
+/// ## Example:
+/// ```
+/// use std::time::Duration;
+/// use random_user::{RandomUserError, RetryPolicy};
+///
+/// // Only retry when rate limited
+/// let retry = RetryPolicy::new(3, Duration::from_secs(1))
+///     .retry_if(|e| matches!(e, RandomUserError::RateLimited { .. }));
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct RetryPolicy {
+    max_attempts: u32,
+    base_delay: Duration,
+    retryable: fn(&RandomUserError) -> bool,
+}
+
+impl RetryPolicy {
+    /// Try at most `max_attempts` times, waiting `base_delay` after the first failure
+    /// and doubling the delay after each following one
+    pub fn new(max_attempts: u32, base_delay: Duration) -> Self {
+        Self {
+            max_attempts: max_attempts.max(1),
+            base_delay,
+            retryable: Self::is_transient,
+        }
+    }
+
+    /// Never retry
+    pub fn none() -> Self {
+        Self::new(1, Duration::ZERO)
+    }
+
+    /// Choose which errors are retried
+    pub fn retry_if(self, retryable: fn(&RandomUserError) -> bool) -> Self {
+        Self { retryable, ..self }
+    }
+
+    pub fn max_attempts(&self) -> u32 {
+        self.max_attempts
+    }
+
+    pub fn base_delay(&self) -> Duration {
+        self.base_delay
+    }
+
+    /// Whether the error is likely to go away by retrying later,
+    /// like timeouts, connection errors, server errors and rate limiting
+    pub fn is_transient(error: &RandomUserError) -> bool {
+        match error {
+            RandomUserError::Reqwest(e) => e.is_connect(),
+            RandomUserError::Timeout => true,
+            RandomUserError::Http { status, .. } => status.is_server_error(),
+            RandomUserError::RateLimited { .. } => true,
+            _ => false,
+        }
+    }
+
+    pub(crate) fn is_retryable(&self, error: &RandomUserError) -> bool {
+        (self.retryable)(error)
+    }
+
+    /// Delay to wait after the given failed attempt, starting at 1
+    pub(crate) fn delay(&self, attempt: u32, error: &RandomUserError) -> Duration {
+        match error {
+            RandomUserError::RateLimited {
+                retry_after: Some(retry_after),
+            } => *retry_after,
+            _ => self
+                .base_delay
+                .saturating_mul(2u32.saturating_pow(attempt.saturating_sub(1))),
+        }
+    }
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self::none()
+    }
+}
@@ -0,0 +1,192 @@
+use crate::generator::RandomUserError;
+use crate::types::RandomUserResult;
+use bytes::Bytes;
+use futures_util::future::BoxFuture;
+use reqwest::header::{HeaderMap, HeaderValue, CONTENT_TYPE};
+use reqwest::StatusCode;
+use std::collections::VecDeque;
+use std::fmt;
+use std::sync::{Mutex, PoisonError};
+
+/// Sends the requests of a generator, to answer them without the network,
+/// record and replay them, route them through a cache, etc.
+///
+/// The response goes through the usual status checks and parsing,
+/// so it must look like one from the api.
+///
+/// Requests and responses are the reqwest ones: the generator builds its requests
+/// with reqwest and reads every response through it, so a transport wrapping a real
+/// client forwards them untouched. Working with http requests rather than queries and
+/// users also lets transports check the headers and answer with any status, like the
+/// cache revalidating with `ETag`s or a fake replaying an api error.
+/// Fakes can answer with [`json_response`], or [`build_response`] for other statuses and headers.
+///
+/// ## Example:
+/// ```
+/// use futures_util::future::BoxFuture;
+/// use random_user::{HttpTransport, RandomUserError, UserGenerator, UserTransport};
+///
+/// // Log every request before sending it to the api
+/// struct Logged(HttpTransport);
+///
+/// impl UserTransport for Logged {
+///     fn send(
+///         &self,
+///         request: reqwest::Request,
+///     ) -> BoxFuture<'_, Result<reqwest::Response, RandomUserError>> {
+///         println!("GET {}", request.url());
+///         self.0.send(request)
+///     }
+/// }
+///
+/// let transport = Logged(HttpTransport::new(reqwest::Client::new()));
+/// let generator = UserGenerator::new().with_transport(transport);
+/// ```
+///
+/// ## Example:
+/// ```
+/// #[tokio::main]
+/// async fn main() {
+///     use futures_util::future::BoxFuture;
+///     use random_user::{json_response, RandomUserError, UserGenerator, UserTransport};
+///
+///     // Answer every request with no users
+///     struct Empty;
+///
+///     impl UserTransport for Empty {
+///         fn send(
+///             &self,
+///             _request: reqwest::Request,
+///         ) -> BoxFuture<'_, Result<reqwest::Response, RandomUserError>> {
+///             let response = json_response(r#"{"results":[]}"#);
+///             Box::pin(async move { Ok(response) })
+///         }
+///     }
+///
+///     let generator = UserGenerator::new().with_transport(Empty);
+///     assert!(generator.get().fetch(5).await.unwrap().is_empty());
+/// }
+/// ```
+///
+/// ## Example:
+/// ```
+/// #[tokio::main]
+/// async fn main() {
+///     use futures_util::future::BoxFuture;
+///     use random_user::{build_response, RandomUserError, UserGenerator, UserTransport};
+///     use reqwest::header::{HeaderMap, HeaderValue, CONTENT_TYPE};
+///     use reqwest::StatusCode;
+///
+///     // Always fail like an overloaded api
+///     struct Unavailable;
+///
+///     impl UserTransport for Unavailable {
+///         fn send(
+///             &self,
+///             _request: reqwest::Request,
+///         ) -> BoxFuture<'_, Result<reqwest::Response, RandomUserError>> {
+///             let mut headers = HeaderMap::new();
+///             headers.insert(CONTENT_TYPE, HeaderValue::from_static("text/plain"));
+///             let response = build_response(StatusCode::SERVICE_UNAVAILABLE, headers, "Try later");
+///             Box::pin(async move { Ok(response) })
+///         }
+///     }
+///
+///     let generator = UserGenerator::new().with_transport(Unavailable);
+///     let err = generator.get().fetch(5).await.unwrap_err();
+///     assert_eq!(err.status(), Some(StatusCode::SERVICE_UNAVAILABLE));
+/// }
+/// ```
+pub trait UserTransport: Send + Sync {
+    fn send(
+        &self,
+        request: reqwest::Request,
+    ) -> BoxFuture<'_, Result<reqwest::Response, RandomUserError>>;
+}
+
+impl fmt::Debug for dyn UserTransport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("UserTransport")
+    }
+}
+
+/// Build a response for a [`UserTransport`], without depending on the http crate
+pub fn build_response(
+    status: StatusCode,
+    headers: HeaderMap,
+    body: impl Into<Bytes>,
+) -> reqwest::Response {
+    let mut response = http::Response::new(body.into());
+    *response.status_mut() = status;
+    *response.headers_mut() = headers;
+    response.into()
+}
+
+/// Build a successful json response for a [`UserTransport`], like the ones of the api
+pub fn json_response(body: impl Into<Bytes>) -> reqwest::Response {
+    let mut headers = HeaderMap::new();
+    headers.insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
+    build_response(StatusCode::OK, headers, body)
+}
+
+/// Transport sending the requests to the api with a reqwest client, used by default
+#[derive(Debug, Clone)]
+pub struct HttpTransport {
+    client: reqwest::Client,
+}
+
+impl HttpTransport {
+    pub fn new(client: reqwest::Client) -> Self {
+        Self { client }
+    }
+}
+
+impl UserTransport for HttpTransport {
+    fn send(
+        &self,
+        request: reqwest::Request,
+    ) -> BoxFuture<'_, Result<reqwest::Response, RandomUserError>> {
+        Box::pin(async move { Ok(self.client.execute(request).await?) })
+    }
+}
+
+/// Transport answering the requests with canned responses, in order
+#[derive(Debug)]
+pub(crate) struct MockTransport {
+    responses: Mutex<VecDeque<RandomUserResult>>,
+}
+
+impl MockTransport {
+    pub(crate) fn new(responses: Vec<RandomUserResult>) -> Self {
+        Self {
+            responses: Mutex::new(responses.into()),
+        }
+    }
+
+    fn respond(&self) -> Result<reqwest::Response, RandomUserError> {
+        let next = self
+            .responses
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .pop_front();
+        let body = match next {
+            Some(result) => {
+                serde_json::to_string(&result).map_err(|source| RandomUserError::Parse {
+                    source,
+                    body: String::new(),
+                })?
+            }
+            None => r#"{"error":"No mock response left"}"#.to_owned(),
+        };
+        Ok(json_response(body))
+    }
+}
+
+impl UserTransport for MockTransport {
+    fn send(
+        &self,
+        _request: reqwest::Request,
+    ) -> BoxFuture<'_, Result<reqwest::Response, RandomUserError>> {
+        Box::pin(async move { self.respond() })
+    }
+}
@@ -1,32 +1,92 @@
-use chrono::{DateTime, Utc};
-use enumset::{EnumSet, EnumSetType};
-use serde::{Deserialize, Serialize};
-use strum::IntoEnumIterator;
-use strum_macros::EnumIter;
-/// Attempts to represent the gender identity of a user
-#[derive(Debug, Deserialize, Serialize, EnumSetType, EnumIter)]
-#[serde(rename_all = "lowercase")]
+use crate::generator::RandomUserError;
+use bytes::Bytes;
+use chrono::{DateTime, Datelike, Utc};
+use serde::de::{self, DeserializeOwned};
+use serde::{Deserialize, Deserializer, Serialize};
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fmt;
+use std::num::ParseFloatError;
+use std::str::FromStr;
+use std::time::Duration;
+use thiserror::Error;
+/// Gender of a user, as returned by the api
+///
+/// Values unknown to this crate are kept in [`Gender::Other`] instead of failing
+///
+/// ## Example:
+/// ```
+/// use random_user::Gender;
+///
+/// let gender: Gender = serde_json::from_str(r#""female""#).unwrap();
+/// assert_eq!(gender, Gender::Female);
+///
+/// let gender: Gender = serde_json::from_str(r#""nonbinary""#).unwrap();
+/// assert_eq!(gender, Gender::Other("nonbinary".to_owned()));
+/// ```
+#[derive(Debug, Clone, Deserialize, Eq, Hash, PartialEq, Serialize)]
+#[serde(from = "String", into = "String")]
 pub enum Gender {
     Female,
     Male,
-    NonBinary,
-    Transgender,
-    Queer,
-    Agender,
-    Other,
-    None,
+    Other(String),
 }
 
 impl Gender {
-    pub fn random_gender() -> EnumSet<Self> {
-        Self::iter().filter(|_| rand::random()).fold(
-            EnumSet::<Self>::EMPTY,
-            |acc: EnumSet<Self>, gender: Self| acc | gender,
-        )
+    /// Name of the gender in the api
+    pub fn as_str(&self) -> &str {
+        match self {
+            Gender::Female => "female",
+            Gender::Male => "male",
+            Gender::Other(gender) => gender,
+        }
+    }
+}
+
+impl From<String> for Gender {
+    fn from(gender: String) -> Self {
+        match gender.as_str() {
+            "female" => Gender::Female,
+            "male" => Gender::Male,
+            _ => Gender::Other(gender),
+        }
+    }
+}
+
+impl From<Gender> for String {
+    fn from(gender: Gender) -> Self {
+        match gender {
+            Gender::Other(gender) => gender,
+            gender => gender.as_str().to_owned(),
+        }
     }
 }
 
-#[derive(Debug, Clone, Copy, Deserialize, PartialEq, Serialize)]
+/// Parse a gender to filter on, ignoring case
+///
+/// Unlike deserialization, only genders the api can filter are accepted.
+///
+/// ## Example:
+/// ```
+/// use random_user::Gender;
+///
+/// assert_eq!("Female".parse::<Gender>(), Ok(Gender::Female));
+/// assert!("robot".parse::<Gender>().is_err());
+/// ```
+impl FromStr for Gender {
+    type Err = ParseError;
+
+    fn from_str(gender: &str) -> Result<Self, Self::Err> {
+        match gender.to_ascii_lowercase().as_str() {
+            "female" => Ok(Gender::Female),
+            "male" => Ok(Gender::Male),
+            _ => Err(ParseError::UnknownGender(gender.to_owned())),
+        }
+    }
+}
+
+/// Nationalities supported by the version 1.4 of the api
+#[derive(Debug, Clone, Copy, Deserialize, Eq, Hash, PartialEq, Serialize)]
 pub enum Nationality {
     #[serde(rename = "AU")]
     Australian,
@@ -72,44 +132,562 @@ pub enum Nationality {
     American,
 }
 
-#[derive(Debug, Clone, Deserialize, PartialEq)]
+/// Fields of a user, to only request some of them
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Field {
+    Gender,
+    Name,
+    Location,
+    Email,
+    Login,
+    Registered,
+    Dob,
+    Phone,
+    Cell,
+    Id,
+    Picture,
+    Nat,
+}
+
+impl Field {
+    /// Name of the field in the api
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Field::Gender => "gender",
+            Field::Name => "name",
+            Field::Location => "location",
+            Field::Email => "email",
+            Field::Login => "login",
+            Field::Registered => "registered",
+            Field::Dob => "dob",
+            Field::Phone => "phone",
+            Field::Cell => "cell",
+            Field::Id => "id",
+            Field::Picture => "picture",
+            Field::Nat => "nat",
+        }
+    }
+
+    pub(crate) fn extend(fields: &mut Vec<Self>, new: &[Self]) {
+        for field in new {
+            if !fields.contains(field) {
+                fields.push(*field);
+            }
+        }
+    }
+
+    pub(crate) fn join(fields: &[Self]) -> String {
+        fields
+            .iter()
+            .map(Self::as_str)
+            .collect::<Vec<_>>()
+            .join(",")
+    }
+}
+
+impl Nationality {
+    /// Every nationality supported by the api
+    ///
+    /// ## Example:
+    /// ```
+    /// use random_user::Nationality;
+    ///
+    /// assert!(Nationality::all().contains(&Nationality::French));
+    /// ```
+    pub fn all() -> &'static [Nationality] {
+        &[
+            Nationality::Australian,
+            Nationality::Brazilian,
+            Nationality::Canadian,
+            Nationality::Swiss,
+            Nationality::German,
+            Nationality::Danish,
+            Nationality::Spanish,
+            Nationality::Finnish,
+            Nationality::French,
+            Nationality::British,
+            Nationality::Irish,
+            Nationality::Indian,
+            Nationality::Iranian,
+            Nationality::Mexican,
+            Nationality::Dutch,
+            Nationality::Norwegian,
+            Nationality::NewZealander,
+            Nationality::Serbian,
+            Nationality::Turkish,
+            Nationality::Ukrainian,
+            Nationality::American,
+        ]
+    }
+
+    /// Two-letter ISO country code of the nationality, as used by the api
+    ///
+    /// ## Example:
+    /// ```
+    /// use random_user::Nationality;
+    ///
+    /// assert_eq!(Nationality::British.as_code(), "GB");
+    ///
+    /// // Same code as the serialized nationality
+    /// for nat in Nationality::all() {
+    ///     assert_eq!(serde_json::to_value(nat).unwrap(), nat.as_code());
+    /// }
+    /// ```
+    pub fn as_code(&self) -> &'static str {
+        match self {
+            Nationality::Australian => "AU",
+            Nationality::Brazilian => "BR",
+            Nationality::Canadian => "CA",
+            Nationality::Swiss => "CH",
+            Nationality::German => "DE",
+            Nationality::Danish => "DK",
+            Nationality::Spanish => "ES",
+            Nationality::Finnish => "FI",
+            Nationality::French => "FR",
+            Nationality::British => "GB",
+            Nationality::Irish => "IE",
+            Nationality::Indian => "IN",
+            Nationality::Iranian => "IR",
+            Nationality::Mexican => "MX",
+            Nationality::Dutch => "NL",
+            Nationality::Norwegian => "NO",
+            Nationality::NewZealander => "NZ",
+            Nationality::Serbian => "RS",
+            Nationality::Turkish => "TR",
+            Nationality::Ukrainian => "UA",
+            Nationality::American => "US",
+        }
+    }
+
+    /// Parse a two-letter ISO country code, ignoring case
+    ///
+    /// ## Example:
+    /// ```
+    /// use random_user::Nationality;
+    ///
+    /// assert_eq!(Nationality::from_code("us"), Some(Nationality::American));
+    /// assert_eq!(Nationality::from_code("XX"), None);
+    /// ```
+    pub fn from_code(code: &str) -> Option<Nationality> {
+        Self::all()
+            .iter()
+            .find(|nat| nat.as_code().eq_ignore_ascii_case(code))
+            .copied()
+    }
+
+    /// English name of the nationality
+    ///
+    /// ## Example:
+    /// ```
+    /// use random_user::Nationality;
+    ///
+    /// assert_eq!(Nationality::NewZealander.name(), "New Zealander");
+    /// ```
+    pub fn name(&self) -> &'static str {
+        match self {
+            Nationality::Australian => "Australian",
+            Nationality::Brazilian => "Brazilian",
+            Nationality::Canadian => "Canadian",
+            Nationality::Swiss => "Swiss",
+            Nationality::German => "German",
+            Nationality::Danish => "Danish",
+            Nationality::Spanish => "Spanish",
+            Nationality::Finnish => "Finnish",
+            Nationality::French => "French",
+            Nationality::British => "British",
+            Nationality::Irish => "Irish",
+            Nationality::Indian => "Indian",
+            Nationality::Iranian => "Iranian",
+            Nationality::Mexican => "Mexican",
+            Nationality::Dutch => "Dutch",
+            Nationality::Norwegian => "Norwegian",
+            Nationality::NewZealander => "New Zealander",
+            Nationality::Serbian => "Serbian",
+            Nationality::Turkish => "Turkish",
+            Nationality::Ukrainian => "Ukrainian",
+            Nationality::American => "American",
+        }
+    }
+
+    pub(crate) fn join(nationalities: &[Self]) -> String {
+        nationalities
+            .iter()
+            .map(Self::as_code)
+            .collect::<Vec<_>>()
+            .join(",")
+    }
+
+    /// English name of the country of the nationality
+    ///
+    /// The variants are named after the nationality, not the country,
+    /// so [`Nationality::British`] is `"United Kingdom"`.
+    ///
+    /// ## Example:
+    /// ```
+    /// use random_user::Nationality;
+    ///
+    /// assert_eq!(Nationality::British.country_name(), "United Kingdom");
+    /// assert_eq!(Nationality::American.to_string(), "United States");
+    /// ```
+    pub fn country_name(&self) -> &'static str {
+        match self {
+            Nationality::Australian => "Australia",
+            Nationality::Brazilian => "Brazil",
+            Nationality::Canadian => "Canada",
+            Nationality::Swiss => "Switzerland",
+            Nationality::German => "Germany",
+            Nationality::Danish => "Denmark",
+            Nationality::Spanish => "Spain",
+            Nationality::Finnish => "Finland",
+            Nationality::French => "France",
+            Nationality::British => "United Kingdom",
+            Nationality::Irish => "Ireland",
+            Nationality::Indian => "India",
+            Nationality::Iranian => "Iran",
+            Nationality::Mexican => "Mexico",
+            Nationality::Dutch => "Netherlands",
+            Nationality::Norwegian => "Norway",
+            Nationality::NewZealander => "New Zealand",
+            Nationality::Serbian => "Serbia",
+            Nationality::Turkish => "Turkey",
+            Nationality::Ukrainian => "Ukraine",
+            Nationality::American => "United States",
+        }
+    }
+}
+
+impl fmt::Display for Nationality {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.country_name())
+    }
+}
+
+/// Parse an ISO country code, a nationality or a country name, ignoring case
+///
+/// ## Example:
+/// ```
+/// use random_user::Nationality;
+///
+/// assert_eq!("GB".parse::<Nationality>(), Ok(Nationality::British));
+/// assert_eq!("british".parse::<Nationality>(), Ok(Nationality::British));
+/// assert_eq!("United Kingdom".parse::<Nationality>(), Ok(Nationality::British));
+/// assert!("Atlantis".parse::<Nationality>().is_err());
+/// ```
+impl FromStr for Nationality {
+    type Err = ParseError;
+
+    fn from_str(nationality: &str) -> Result<Self, Self::Err> {
+        Self::all()
+            .iter()
+            .find(|nat| {
+                [nat.as_code(), nat.name(), nat.country_name()]
+                    .iter()
+                    .any(|name| name.eq_ignore_ascii_case(nationality))
+            })
+            .copied()
+            .ok_or_else(|| ParseError::UnknownNationality(nationality.to_owned()))
+    }
+}
+
+/// Error parsing a filter value from a string
+#[derive(Debug, Error, PartialEq)]
+pub enum ParseError {
+    #[error("Unknown nationality: {0}")]
+    UnknownNationality(String),
+    #[error("Unknown gender: {0}")]
+    UnknownGender(String),
+}
+
+/// Output formats of the api
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Format {
+    Json,
+    Yaml,
+    Xml,
+    Csv,
+}
+
+impl Format {
+    /// Name of the format in the api
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Format::Json => "json",
+            Format::Yaml => "yaml",
+            Format::Xml => "xml",
+            Format::Csv => "csv",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, PartialEq, Serialize)]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
 pub struct Name {
     pub title: String,
     pub first: String,
     pub last: String,
 }
 
-#[derive(Debug, Clone, Deserialize, PartialEq)]
+/// ## Example:
+/// ```
+/// use random_user::Name;
+///
+/// let name = Name {
+///     title: "Mr".to_owned(),
+///     first: "John".to_owned(),
+///     last: "Smith".to_owned(),
+/// };
+/// assert_eq!(name.to_string(), "Mr John Smith");
+/// ```
+impl fmt::Display for Name {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {} {}", self.title, self.first, self.last)
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, PartialEq, Serialize)]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
 pub struct Street {
     pub number: i32,
     pub name: String,
 }
 
-#[derive(Debug, Clone, Deserialize, PartialEq)]
+/// Coordinates of a location, in degrees
+///
+/// Coordinates returned as numbers by some api versions are kept as strings too
+///
+/// ## Example:
+/// ```
+/// use random_user::Coordinates;
+///
+/// let coordinates: Coordinates =
+///     serde_json::from_str(r#"{"latitude": "-69.8246", "longitude": 134.8719}"#).unwrap();
+/// assert_eq!(coordinates.latitude, "-69.8246");
+/// assert_eq!(coordinates.longitude, "134.8719");
+/// ```
+#[derive(Debug, Clone, Deserialize, PartialEq, Serialize)]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
 pub struct Coordinates {
+    #[serde(deserialize_with = "string_or_number")]
     pub latitude: String,
+    #[serde(deserialize_with = "string_or_number")]
     pub longitude: String,
 }
 
-#[derive(Debug, Clone, Deserialize, PartialEq)]
+impl Coordinates {
+    /// Parse the latitude in degrees
+    ///
+    /// ## Example:
+    /// ```
+    /// use random_user::Coordinates;
+    ///
+    /// let coordinates = Coordinates {
+    ///     latitude: "-69.8246".to_owned(),
+    ///     longitude: "134.8719".to_owned(),
+    /// };
+    /// assert_eq!(coordinates.latitude_f64(), Ok(-69.8246));
+    /// assert_eq!(coordinates.longitude_f64(), Ok(134.8719));
+    /// ```
+    pub fn latitude_f64(&self) -> Result<f64, ParseFloatError> {
+        self.latitude.parse()
+    }
+
+    /// Parse the longitude in degrees
+    pub fn longitude_f64(&self) -> Result<f64, ParseFloatError> {
+        self.longitude.parse()
+    }
+}
+
+fn string_or_number<'de, D: Deserializer<'de>>(deserializer: D) -> Result<String, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum StringOrNumber {
+        String(String),
+        Number(f64),
+    }
+
+    Ok(match StringOrNumber::deserialize(deserializer)? {
+        StringOrNumber::String(value) => value,
+        StringOrNumber::Number(value) => value.to_string(),
+    })
+}
+
+#[derive(Debug, Clone, Deserialize, PartialEq, Serialize)]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
 pub struct Timezone {
     pub offset: String,
     pub description: String,
 }
 
-#[derive(Debug, Clone, Deserialize, PartialEq)]
+impl Timezone {
+    /// Parse the offset from UTC in minutes, the sign applying to the whole offset
+    ///
+    /// ## Example:
+    /// ```
+    /// use random_user::Timezone;
+    ///
+    /// let timezone = Timezone {
+    ///     offset: "-3:30".to_owned(),
+    ///     description: "Newfoundland".to_owned(),
+    /// };
+    /// assert_eq!(timezone.offset_minutes().unwrap(), -210);
+    ///
+    /// let timezone = Timezone {
+    ///     offset: "+5:45".to_owned(),
+    ///     description: "Kathmandu".to_owned(),
+    /// };
+    /// assert_eq!(timezone.offset_minutes().unwrap(), 345);
+    /// ```
+    pub fn offset_minutes(&self) -> Result<i32, RandomUserError> {
+        let invalid = || RandomUserError::InvalidOffset(self.offset.clone());
+        let (sign, offset) = match self.offset.strip_prefix('-') {
+            Some(offset) => (-1, offset),
+            None => (1, self.offset.strip_prefix('+').unwrap_or(&self.offset)),
+        };
+        let (hours, minutes) = offset.split_once(':').unwrap_or((offset, "0"));
+        let hours: i32 = hours.parse().map_err(|_| invalid())?;
+        let minutes: i32 = minutes.parse().map_err(|_| invalid())?;
+        if !(0..=14).contains(&hours) || !(0..60).contains(&minutes) {
+            return Err(invalid());
+        }
+        Ok(sign * (hours * 60 + minutes))
+    }
+}
+
+/// Postcode of a location, numeric for some nationalities and alphanumeric for others
+///
+/// ## Example:
+/// ```
+/// use random_user::Postcode;
+///
+/// let postcode: Postcode = serde_json::from_str("12345").unwrap();
+/// assert_eq!(postcode, Postcode::Numeric(12345));
+///
+/// let postcode: Postcode = serde_json::from_str(r#""SW1A 1AA""#).unwrap();
+/// assert_eq!(postcode.to_string(), "SW1A 1AA");
+/// ```
+#[derive(Debug, Clone, Deserialize, PartialEq, Serialize)]
+#[serde(untagged)]
+pub enum Postcode {
+    Numeric(u64),
+    Alpha(String),
+}
+
+impl fmt::Display for Postcode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Postcode::Numeric(postcode) => write!(f, "{postcode}"),
+            Postcode::Alpha(postcode) => f.write_str(postcode),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, PartialEq, Serialize)]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
 pub struct Location {
     pub street: Street,
     pub city: String,
     pub state: String,
     pub country: String,
-    #[serde(deserialize_with = "deserialize_as_string")]
-    pub postcode: String,
+    pub postcode: Postcode,
     pub coordinates: Coordinates,
     pub timezone: Timezone,
 }
 
-#[derive(Debug, Clone, Deserialize, PartialEq)]
+impl Location {
+    /// City with the first letter of each word in uppercase and the others in lowercase
+    ///
+    /// ## Example:
+    /// ```
+    /// use random_user::{Coordinates, Location, Postcode, Street, Timezone};
+    ///
+    /// let location = Location {
+    ///     street: Street {
+    ///         number: 12,
+    ///         name: "Rue de la Paix".to_owned(),
+    ///     },
+    ///     city: "SAINT-ÉTIENNE".to_owned(),
+    ///     state: "loire".to_owned(),
+    ///     country: "France".to_owned(),
+    ///     postcode: Postcode::Numeric(42000),
+    ///     coordinates: Coordinates {
+    ///         latitude: "45.4397".to_owned(),
+    ///         longitude: "4.3872".to_owned(),
+    ///     },
+    ///     timezone: Timezone {
+    ///         offset: "+1:00".to_owned(),
+    ///         description: "Paris".to_owned(),
+    ///     },
+    /// };
+    /// assert_eq!(location.city_titlecase(), "Saint-Étienne");
+    /// assert_eq!(location.state_titlecase(), "Loire");
+    /// ```
+    pub fn city_titlecase(&self) -> String {
+        title_case(&self.city)
+    }
+
+    /// State with the first letter of each word in uppercase and the others in lowercase
+    pub fn state_titlecase(&self) -> String {
+        title_case(&self.state)
+    }
+
+    /// Single line address, like `12 Rue de la Paix, Saint-Étienne, Loire 42000, France`
+    ///
+    /// ## Example:
+    /// ```
+    /// use random_user::{Coordinates, Location, Postcode, Street, Timezone};
+    ///
+    /// let location = Location {
+    ///     street: Street {
+    ///         number: 12,
+    ///         name: "Rue de la Paix".to_owned(),
+    ///     },
+    ///     city: "Saint-Étienne".to_owned(),
+    ///     state: "Loire".to_owned(),
+    ///     country: "France".to_owned(),
+    ///     postcode: Postcode::Numeric(42000),
+    ///     coordinates: Coordinates {
+    ///         latitude: "45.4397".to_owned(),
+    ///         longitude: "4.3872".to_owned(),
+    ///     },
+    ///     timezone: Timezone {
+    ///         offset: "+1:00".to_owned(),
+    ///         description: "Paris".to_owned(),
+    ///     },
+    /// };
+    /// assert_eq!(
+    ///     location.full_address(),
+    ///     "12 Rue de la Paix, Saint-Étienne, Loire 42000, France"
+    /// );
+    /// ```
+    pub fn full_address(&self) -> String {
+        format!(
+            "{} {}, {}, {} {}, {}",
+            self.street.number,
+            self.street.name,
+            self.city,
+            self.state,
+            self.postcode,
+            self.country
+        )
+    }
+}
+
+fn title_case(text: &str) -> String {
+    let mut title = String::with_capacity(text.len());
+    let mut word_start = true;
+    for c in text.chars() {
+        if word_start {
+            title.extend(c.to_uppercase());
+        } else {
+            title.extend(c.to_lowercase());
+        }
+        word_start = c.is_whitespace() || c == '-';
+    }
+    title
+}
+
+#[derive(Debug, Clone, Deserialize, PartialEq, Serialize)]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
 pub struct Login {
     pub uuid: String,
     pub username: String,
@@ -120,28 +698,296 @@ pub struct Login {
     pub sha256: String,
 }
 
-#[derive(Debug, Clone, Deserialize, PartialEq)]
+impl Login {
+    /// Decode the md5 hash of the password
+    ///
+    /// Returns an error if the hash is not 32 hexadecimal digits
+    ///
+    /// ## Example:
+    /// ```
+    /// use random_user::Login;
+    ///
+    /// let login = Login {
+    ///     uuid: "7a0eed16-9430-4d68-901f-c0d4c1c3bf00".to_owned(),
+    ///     username: "yellowpeacock117".to_owned(),
+    ///     password: "addison".to_owned(),
+    ///     salt: "sld1yGtd".to_owned(),
+    ///     md5: "ab54ac4c0be9480ae8fa5e9e2a5196a3".to_owned(),
+    ///     sha1: "edcf2ce613cbdea349133c52dc2f3b83168dc51b".to_owned(),
+    ///     sha256: "not hex".to_owned(),
+    /// };
+    /// assert_eq!(login.md5_bytes().unwrap()[..2], [0xab, 0x54]);
+    /// assert_eq!(login.sha1_bytes().unwrap()[19], 0x1b);
+    /// assert!(login.sha256_bytes().is_err());
+    /// ```
+    pub fn md5_bytes(&self) -> Result<[u8; 16], RandomUserError> {
+        decode_hex(&self.md5)
+    }
+
+    /// Decode the sha1 hash of the password, see [`md5_bytes`](Self::md5_bytes)
+    pub fn sha1_bytes(&self) -> Result<[u8; 20], RandomUserError> {
+        decode_hex(&self.sha1)
+    }
+
+    /// Decode the sha256 hash of the password, see [`md5_bytes`](Self::md5_bytes)
+    pub fn sha256_bytes(&self) -> Result<[u8; 32], RandomUserError> {
+        decode_hex(&self.sha256)
+    }
+}
+
+fn decode_hex<const N: usize>(hex: &str) -> Result<[u8; N], RandomUserError> {
+    let invalid = || RandomUserError::InvalidHash(hex.to_owned());
+    if hex.len() != N * 2 || !hex.bytes().all(|c| c.is_ascii_hexdigit()) {
+        return Err(invalid());
+    }
+    let mut bytes = [0; N];
+    for (byte, i) in bytes.iter_mut().zip((0..hex.len()).step_by(2)) {
+        *byte = u8::from_str_radix(&hex[i..i + 2], 16).map_err(|_| invalid())?;
+    }
+    Ok(bytes)
+}
+
+/// Date of birth or registration of a user
+///
+/// Dates with an offset are converted to UTC, keeping the same instant.
+///
+/// ## Example:
+/// ```
+/// use chrono::{TimeZone, Utc};
+/// use random_user::RandomDate;
+///
+/// let date: RandomDate =
+///     serde_json::from_str(r#"{"date": "1993-07-20T09:44:18.674+05:30", "age": 30}"#).unwrap();
+/// assert_eq!(
+///     date.date,
+///     Utc.with_ymd_and_hms(1993, 7, 20, 4, 14, 18).unwrap() + chrono::Duration::milliseconds(674)
+/// );
+/// ```
+#[derive(Debug, Clone, Deserialize, PartialEq, Serialize)]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
 pub struct RandomDate {
     pub date: DateTime<Utc>,
     pub age: i32,
 }
 
-#[derive(Debug, Clone, Deserialize, PartialEq)]
+impl RandomDate {
+    /// Age in whole years as of now
+    ///
+    /// Unlike `age`, which is computed by the api when the user is generated,
+    /// this is always up to date.
+    pub fn age_now(&self) -> i64 {
+        self.age_at(Utc::now())
+    }
+
+    /// Age in whole years as of the given date
+    ///
+    /// Someone born on February 29 gets one year older on March 1 in non-leap years.
+    ///
+    /// ## Example:
+    /// ```
+    /// use chrono::{TimeZone, Utc};
+    /// use random_user::RandomDate;
+    ///
+    /// let birthday = RandomDate {
+    ///     date: Utc.with_ymd_and_hms(2000, 2, 29, 12, 0, 0).unwrap(),
+    ///     age: 0,
+    /// };
+    /// assert_eq!(birthday.age_at(Utc.with_ymd_and_hms(2023, 2, 28, 12, 0, 0).unwrap()), 22);
+    /// assert_eq!(birthday.age_at(Utc.with_ymd_and_hms(2023, 3, 1, 0, 0, 0).unwrap()), 23);
+    /// assert_eq!(birthday.age_at(Utc.with_ymd_and_hms(2024, 2, 29, 0, 0, 0).unwrap()), 24);
+    /// ```
+    pub fn age_at(&self, date: DateTime<Utc>) -> i64 {
+        let age = i64::from(date.year() - self.date.year());
+        if (date.month(), date.day()) < (self.date.month(), self.date.day()) {
+            age - 1
+        } else {
+            age
+        }
+    }
+
+    /// Year of the date, in UTC
+    ///
+    /// ## Example:
+    /// ```
+    /// use random_user::RandomDate;
+    ///
+    /// let date: RandomDate =
+    ///     serde_json::from_str(r#"{"date": "1993-07-20T09:44:18.674Z", "age": 30}"#).unwrap();
+    /// assert_eq!((date.year(), date.month(), date.day()), (1993, 7, 20));
+    /// ```
+    pub fn year(&self) -> i32 {
+        self.date.year()
+    }
+
+    /// Month of the date, from 1 to 12, in UTC
+    pub fn month(&self) -> u32 {
+        self.date.month()
+    }
+
+    /// Day of the month, from 1 to 31, in UTC
+    pub fn day(&self) -> u32 {
+        self.date.day()
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, PartialEq, Serialize)]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
 pub struct Identity {
     pub name: String,
     pub value: Option<String>,
 }
 
-#[derive(Debug, Clone, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Deserialize, PartialEq, Serialize)]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
 pub struct Picture {
     pub large: String,
     pub medium: String,
     pub thumbnail: String,
 }
 
-#[derive(Debug, Clone, Deserialize, PartialEq)]
+/// Available sizes of a user picture
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PictureSize {
+    Large,
+    Medium,
+    Thumbnail,
+}
+
+impl Picture {
+    /// Url of the picture with the given size
+    pub fn url(&self, size: PictureSize) -> &str {
+        match size {
+            PictureSize::Large => &self.large,
+            PictureSize::Medium => &self.medium,
+            PictureSize::Thumbnail => &self.thumbnail,
+        }
+    }
+
+    /// Numeric id of the portrait, parsed from its url
+    ///
+    /// The api can't filter portraits, but the set and id tell which one a user got.
+    /// Ids are only unique within a set, like `men` or `women`.
+    ///
+    /// ## Example:
+    /// ```
+    /// use random_user::Picture;
+    ///
+    /// let picture = Picture {
+    ///     large: "https://randomuser.me/api/portraits/men/75.jpg".to_owned(),
+    ///     medium: "https://randomuser.me/api/portraits/med/men/75.jpg".to_owned(),
+    ///     thumbnail: "https://randomuser.me/api/portraits/thumb/men/75.jpg".to_owned(),
+    /// };
+    /// assert_eq!(picture.id(), Some("75"));
+    /// assert_eq!(picture.set(), Some("men"));
+    /// ```
+    pub fn id(&self) -> Option<&str> {
+        let (_, file) = self.large.rsplit_once('/')?;
+        let id = file.split_once('.').map_or(file, |(id, _)| id);
+        Some(id).filter(|id| !id.is_empty() && id.bytes().all(|b| b.is_ascii_digit()))
+    }
+
+    /// Set of the portrait, parsed from its url
+    pub fn set(&self) -> Option<&str> {
+        let (path, _) = self.large.rsplit_once('/')?;
+        let (_, set) = path.rsplit_once('/')?;
+        Some(set)
+    }
+
+    /// Download the picture with the given size
+    ///
+    /// ## Example:
+    /// ```no_run
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     use random_user::{PictureSize, UserGenerator};
+    ///
+    ///     let generator = UserGenerator::new();
+    ///     let user = generator.fetch_one().await.unwrap();
+    ///
+    ///     let client = reqwest::Client::new();
+    ///     let image = user.picture.fetch(&client, PictureSize::Large).await.unwrap();
+    ///
+    ///     std::fs::write("picture.jpg", image).unwrap();
+    /// }
+    /// ```
+    pub async fn fetch(
+        &self,
+        client: &reqwest::Client,
+        size: PictureSize,
+    ) -> Result<Bytes, RandomUserError> {
+        let rsp = client
+            .get(self.url(size))
+            .send()
+            .await?
+            .error_for_status()?;
+        Ok(rsp.bytes().await?)
+    }
+}
+
+/// Phone number, formatted according to the nationality of the user
+///
+/// ## Example:
+/// ```
+/// use random_user::PhoneNumber;
+///
+/// let phone: PhoneNumber = serde_json::from_str(r#""(272) 790-0888""#).unwrap();
+/// assert_eq!(phone.raw(), "(272) 790-0888");
+/// assert_eq!(phone.digits(), "2727900888");
+/// assert_eq!(phone, PhoneNumber::new("(272) 790-0888"));
+/// ```
+#[derive(Debug, Clone, Deserialize, PartialEq, Serialize)]
+#[serde(transparent)]
+pub struct PhoneNumber(pub(crate) String);
+
+impl PhoneNumber {
+    /// Phone number formatted in any way, to build users by hand
+    pub fn new(phone: impl Into<String>) -> Self {
+        Self(phone.into())
+    }
+
+    /// Phone number as returned by the api
+    pub fn raw(&self) -> &str {
+        &self.0
+    }
+
+    /// Phone number without formatting, only keeping the digits
+    pub fn digits(&self) -> String {
+        self.0.chars().filter(char::is_ascii_digit).collect()
+    }
+}
+
+impl From<String> for PhoneNumber {
+    fn from(phone: String) -> Self {
+        Self(phone)
+    }
+}
+
+impl fmt::Display for PhoneNumber {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+/// User generated by the api
+///
+/// With the `strict` feature, this type and the ones it contains fail to deserialize
+/// when the api returns fields unknown to this crate, instead of ignoring them,
+/// to notice changes of the api early.
+///
+/// ## Example:
+/// ```
+/// use random_user::Name;
+///
+/// let name = serde_json::from_str::<Name>(
+///     r#"{"title": "Ms", "first": "Jane", "last": "Doe", "middle": "Ann"}"#,
+/// );
+/// assert_eq!(name.is_err(), cfg!(feature = "strict"));
+/// ```
+#[derive(Debug, Clone, Deserialize, PartialEq, Serialize)]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
 pub struct RandomUser {
-    pub gender: EnumSet<Gender>,
+    /// Gender as returned by the api, never replaced by the requested one
+    pub gender: Gender,
     pub name: Name,
     pub location: Location,
     pub email: String,
@@ -149,15 +995,231 @@ pub struct RandomUser {
     #[serde(rename = "dob")]
     pub birthday: RandomDate,
     pub registered: RandomDate,
-    pub phone: String,
-    pub cell: String,
+    pub phone: PhoneNumber,
+    pub cell: PhoneNumber,
     pub id: Identity,
     pub picture: Picture,
     #[serde(rename = "nat")]
     pub nationality: Nationality,
 }
 
-#[derive(Debug, Clone, Deserialize, PartialEq)]
+impl RandomUser {
+    /// Compare by last name, then by first name
+    ///
+    /// ## Example:
+    /// ```no_run
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     use random_user::{RandomUser, UserGenerator};
+    ///
+    ///     let mut users = UserGenerator::new().fetch(10).await.unwrap();
+    ///     users.sort_by(RandomUser::cmp_by_last_name);
+    /// }
+    /// ```
+    pub fn cmp_by_last_name(&self, other: &Self) -> Ordering {
+        (&self.name.last, &self.name.first).cmp(&(&other.name.last, &other.name.first))
+    }
+
+    /// Compare by age, youngest first
+    pub fn cmp_by_age(&self, other: &Self) -> Ordering {
+        other.birthday.date.cmp(&self.birthday.date)
+    }
+
+    /// Flat view of the user, with dotted keys named like the api fields,
+    /// such as `name.first`, `location.city` or `dob.age`
+    ///
+    /// Missing values are empty strings.
+    ///
+    /// ## Example:
+    /// ```no_run
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     use random_user::UserGenerator;
+    ///
+    ///     let user = UserGenerator::new().fetch_one().await.unwrap();
+    ///     let fields = user.flatten();
+    ///
+    ///     assert_eq!(fields["login.username"], user.login.username);
+    ///     assert_eq!(fields["nat"], user.nationality.as_code());
+    /// }
+    /// ```
+    pub fn flatten(&self) -> BTreeMap<String, String> {
+        let mut fields = BTreeMap::new();
+        flatten_into(&mut fields, String::new(), self.to_json());
+        fields
+    }
+
+    /// Json value of the user, in the same shape as the api, to embed in other payloads
+    ///
+    /// ## Example:
+    /// ```no_run
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     use random_user::UserGenerator;
+    ///
+    ///     let user = UserGenerator::new().fetch_one().await.unwrap();
+    ///     let payload = serde_json::json!({ "source": "randomuser.me", "user": user.to_json() });
+    ///
+    ///     assert_eq!(payload["user"]["name"]["first"], user.name.first);
+    /// }
+    /// ```
+    pub fn to_json(&self) -> serde_json::Value {
+        serde_json::to_value(self).expect("Users serialize to json")
+    }
+}
+
+fn flatten_into(fields: &mut BTreeMap<String, String>, key: String, value: serde_json::Value) {
+    match value {
+        serde_json::Value::Object(map) => {
+            for (name, value) in map {
+                let key = if key.is_empty() {
+                    name
+                } else {
+                    format!("{key}.{name}")
+                };
+                flatten_into(fields, key, value);
+            }
+        }
+        serde_json::Value::Null => {
+            fields.insert(key, String::new());
+        }
+        serde_json::Value::String(value) => {
+            fields.insert(key, value);
+        }
+        value => {
+            fields.insert(key, value.to_string());
+        }
+    }
+}
+
+/// Short summary of the user, like `Mr John Smith <john.smith@example.com> (US)`
+impl fmt::Display for RandomUser {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} <{}> ({})",
+            self.name,
+            self.email,
+            self.nationality.as_code()
+        )
+    }
+}
+
+/// A user without its login, so without any password, salt or hash
+///
+/// Fetched with [`fetch_lite`](crate::UserGeneratorBuilder::fetch_lite),
+/// or converted from a [`RandomUser`] to drop its credentials.
+#[derive(Debug, Clone, Deserialize, PartialEq, Serialize)]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
+pub struct RandomUserLite {
+    pub gender: Gender,
+    pub name: Name,
+    pub location: Location,
+    pub email: String,
+    #[serde(rename = "dob")]
+    pub birthday: RandomDate,
+    pub registered: RandomDate,
+    pub phone: PhoneNumber,
+    pub cell: PhoneNumber,
+    pub id: Identity,
+    pub picture: Picture,
+    #[serde(rename = "nat")]
+    pub nationality: Nationality,
+}
+
+impl From<RandomUser> for RandomUserLite {
+    fn from(user: RandomUser) -> Self {
+        Self {
+            gender: user.gender,
+            name: user.name,
+            location: user.location,
+            email: user.email,
+            birthday: user.birthday,
+            registered: user.registered,
+            phone: user.phone,
+            cell: user.cell,
+            id: user.id,
+            picture: user.picture,
+            nationality: user.nationality,
+        }
+    }
+}
+
+/// A user where every field is optional, when only some fields are requested
+#[derive(Debug, Clone, Deserialize, PartialEq, Serialize)]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
+pub struct PartialUser {
+    pub gender: Option<Gender>,
+    pub name: Option<Name>,
+    pub location: Option<Location>,
+    pub email: Option<String>,
+    pub login: Option<Login>,
+    #[serde(rename = "dob")]
+    pub birthday: Option<RandomDate>,
+    pub registered: Option<RandomDate>,
+    pub phone: Option<PhoneNumber>,
+    pub cell: Option<PhoneNumber>,
+    pub id: Option<Identity>,
+    pub picture: Option<Picture>,
+    #[serde(rename = "nat")]
+    pub nationality: Option<Nationality>,
+}
+
+impl PartialUser {
+    /// Convert into a [`RandomUser`], if no field is missing
+    ///
+    /// ## Example:
+    /// ```no_run
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     use random_user::UserGenerator;
+    ///
+    ///     let generator = UserGenerator::new();
+    ///
+    ///     let users = generator.get().fetch_partial(5).await.unwrap();
+    ///     let users: Option<Vec<_>> = users.into_iter().map(|u| u.into_user()).collect();
+    ///     assert!(users.is_some());
+    /// }
+    /// ```
+    pub fn into_user(self) -> Option<RandomUser> {
+        Some(RandomUser {
+            gender: self.gender?,
+            name: self.name?,
+            location: self.location?,
+            email: self.email?,
+            login: self.login?,
+            birthday: self.birthday?,
+            registered: self.registered?,
+            phone: self.phone?,
+            cell: self.cell?,
+            id: self.id?,
+            picture: self.picture?,
+            nationality: self.nationality?,
+        })
+    }
+}
+
+impl From<RandomUser> for PartialUser {
+    fn from(user: RandomUser) -> Self {
+        Self {
+            gender: Some(user.gender),
+            name: Some(user.name),
+            location: Some(user.location),
+            email: Some(user.email),
+            login: Some(user.login),
+            birthday: Some(user.birthday),
+            registered: Some(user.registered),
+            phone: Some(user.phone),
+            cell: Some(user.cell),
+            id: Some(user.id),
+            picture: Some(user.picture),
+            nationality: Some(user.nationality),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, PartialEq, Serialize)]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
 pub struct RandomUserInfo {
     pub seed: String,
     pub results: i32,
@@ -165,27 +1227,259 @@ pub struct RandomUserInfo {
     pub version: String,
 }
 
-#[derive(Debug, Clone, Deserialize, PartialEq)]
-pub struct RandomUserResult {
-    pub results: Vec<RandomUser>,
+impl RandomUserInfo {
+    /// Seed of the dataset, to request the same users again
+    pub fn seed(&self) -> &str {
+        &self.seed
+    }
+
+    /// Major and minor numbers of the api version, `None` if it isn't like `1.4`
+    ///
+    /// ## Example:
+    /// ```
+    /// use random_user::RandomUserInfo;
+    ///
+    /// let info: RandomUserInfo = serde_json::from_str(
+    ///     r#"{"seed": "foobar", "results": 5, "page": 1, "version": "1.4"}"#,
+    /// )
+    /// .unwrap();
+    /// assert_eq!(info.version_parts(), Some((1, 4)));
+    /// assert_eq!(info.version, "1.4");
+    /// ```
+    pub fn version_parts(&self) -> Option<(u32, u32)> {
+        let (major, minor) = self.version.split_once('.')?;
+        Some((major.parse().ok()?, minor.parse().ok()?))
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, PartialEq, Serialize)]
+#[cfg_attr(feature = "strict", serde(deny_unknown_fields))]
+pub struct RandomUserResult<T = RandomUser> {
+    pub results: Vec<T>,
     pub info: RandomUserInfo,
 }
 
-#[derive(Debug, Clone, Deserialize, PartialEq)]
-pub enum RandomUserResponse {
+impl<T> RandomUserResult<T> {
+    /// Iterate over the users
+    pub fn iter(&self) -> std::slice::Iter<'_, T> {
+        self.results.iter()
+    }
+
+    /// Seed of the users, given or picked at random by the api
+    ///
+    /// The same users can be generated again with this seed, the same page and count.
+    ///
+    /// ## Example:
+    /// ```no_run
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     use random_user::UserGenerator;
+    ///
+    ///     let generator = UserGenerator::new();
+    ///
+    ///     let result = generator.fetch_with_info(10).await.unwrap();
+    ///     let seed = result.seed().to_owned();
+    ///
+    ///     // Later on
+    ///     let users = generator.get().seed(&seed).page(1).fetch(10).await.unwrap();
+    ///     assert_eq!(users, result.results);
+    /// }
+    /// ```
+    pub fn seed(&self) -> &str {
+        &self.info.seed
+    }
+
+    /// Whether fewer users than requested were returned
+    ///
+    /// Counts above [`MAX_RESULTS`](crate::UserGeneratorBuilder::MAX_RESULTS) are already
+    /// rejected before sending the request, this catches the api returning less anyway.
+    pub fn is_truncated(&self, requested: usize) -> bool {
+        self.results.len() < requested
+    }
+}
+
+impl RandomUserResult {
+    /// Remove users with the same username as a previous one
+    pub fn dedup_by_username(&mut self) {
+        let mut usernames = HashSet::new();
+        self.results
+            .retain(|user| usernames.insert(user.login.username.clone()));
+    }
+
+    /// Remove users with the same email as a previous one
+    pub fn dedup_by_email(&mut self) {
+        let mut emails = HashSet::new();
+        self.results
+            .retain(|user| emails.insert(user.email.clone()));
+    }
+
+    /// Group the users by nationality
+    ///
+    /// ## Example:
+    /// ```no_run
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     use random_user::{Nationality, UserGenerator};
+    ///
+    ///     let result = UserGenerator::new()
+    ///         .get()
+    ///         .nationalities(&[Nationality::French, Nationality::Irish])
+    ///         .fetch_with_info(20)
+    ///         .await
+    ///         .unwrap();
+    ///
+    ///     for (nationality, users) in result.by_nationality() {
+    ///         println!("{}: {} users", nationality, users.len());
+    ///     }
+    /// }
+    /// ```
+    pub fn by_nationality(&self) -> HashMap<Nationality, Vec<&RandomUser>> {
+        let mut groups: HashMap<Nationality, Vec<&RandomUser>> = HashMap::new();
+        for user in &self.results {
+            groups.entry(user.nationality).or_default().push(user);
+        }
+        groups
+    }
+
+    /// Users sorted by age, youngest first
+    pub fn sorted_by_age(&self) -> Vec<&RandomUser> {
+        let mut users: Vec<&RandomUser> = self.results.iter().collect();
+        users.sort_by(|a, b| a.cmp_by_age(b));
+        users
+    }
+
+    /// Group the users by gender
+    pub fn by_gender(&self) -> HashMap<Gender, Vec<&RandomUser>> {
+        let mut groups: HashMap<Gender, Vec<&RandomUser>> = HashMap::new();
+        for user in &self.results {
+            groups.entry(user.gender.clone()).or_default().push(user);
+        }
+        groups
+    }
+
+    /// Number of users of each gender
+    ///
+    /// ## Example:
+    /// ```no_run
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     use random_user::{Gender, UserGenerator};
+    ///
+    ///     let result = UserGenerator::new().fetch_with_info(100).await.unwrap();
+    ///     let counts = result.gender_counts();
+    ///
+    ///     println!("{} women", counts.get(&Gender::Female).unwrap_or(&0));
+    /// }
+    /// ```
+    pub fn gender_counts(&self) -> HashMap<Gender, usize> {
+        let mut counts = HashMap::new();
+        for user in &self.results {
+            *counts.entry(user.gender.clone()).or_default() += 1;
+        }
+        counts
+    }
+}
+
+impl<T> IntoIterator for RandomUserResult<T> {
+    type Item = T;
+    type IntoIter = std::vec::IntoIter<T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.results.into_iter()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a RandomUserResult<T> {
+    type Item = &'a T;
+    type IntoIter = std::slice::Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.results.iter()
+    }
+}
+
+/// Performance of a request
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FetchStats {
+    pub duration: Duration,
+    pub bytes: usize,
+}
+
+/// Body of an api response, either an error message or the users
+///
+/// Any body with a non-null `error` key is an error, whatever its other keys.
+///
+/// ## Example:
+/// ```
+/// use random_user::RandomUserResponse;
+///
+/// let response: RandomUserResponse =
+///     serde_json::from_str(r#"{"error": "Uh oh, something has gone wrong.", "results": []}"#)
+///         .unwrap();
+/// assert_eq!(
+///     response,
+///     RandomUserResponse::Error("Uh oh, something has gone wrong.".to_owned())
+/// );
+///
+/// let response: RandomUserResponse = serde_json::from_str(
+///     r#"{"results": [], "info": {"seed": "foobar", "results": 0, "page": 1, "version": "1.4"}}"#,
+/// )
+/// .unwrap();
+/// assert!(matches!(response, RandomUserResponse::Result(_)));
+///
+/// let response: RandomUserResponse = serde_json::from_str(
+///     r#"{"error": null, "results": [], "info": {"seed": "foobar", "results": 0, "page": 1, "version": "1.4"}}"#,
+/// )
+/// .unwrap();
+/// assert!(matches!(response, RandomUserResponse::Result(_)));
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub enum RandomUserResponse<T = RandomUser> {
     #[serde(rename = "error")]
     Error(String),
     #[serde(untagged)]
-    Result(RandomUserResult),
+    Result(RandomUserResult<T>),
+}
+
+impl<'de, T: DeserializeOwned> Deserialize<'de> for RandomUserResponse<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Ok(match ApiResponse::deserialize(deserializer)? {
+            ApiResponse::Error(error) => RandomUserResponse::Error(error),
+            ApiResponse::Result(res) => RandomUserResponse::Result(res),
+        })
+    }
+}
+
+/// Body of any api response, either its error message or the requested content
+///
+/// An error is a body with a non-null `error` key, see [`api_error`].
+/// Checking the key explicitly doesn't depend on how an untagged enum tries its variants.
+pub(crate) enum ApiResponse<T> {
+    Error(String),
+    Result(T),
+}
+
+impl<'de, T: DeserializeOwned> Deserialize<'de> for ApiResponse<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let mut body = serde_json::Value::deserialize(deserializer)?;
+        if let Some(error) = api_error(&body) {
+            return Ok(ApiResponse::Error(error));
+        }
+        // Removed so that strict parsing doesn't reject a null error
+        if let Some(body) = body.as_object_mut() {
+            body.remove("error");
+        }
+        T::deserialize(body)
+            .map(ApiResponse::Result)
+            .map_err(de::Error::custom)
+    }
 }
 
-fn deserialize_as_string<'de, D>(deserializer: D) -> Result<String, D::Error>
-where
-    D: serde::Deserializer<'de>,
-{
-    let json: serde_json::value::Value = serde_json::value::Value::deserialize(deserializer)?;
-    match json {
-        serde_json::Value::String(s) => Ok(s),
-        _ => Ok(json.to_string()),
+/// Error message of an api response body, if it has a non-null `error` key
+pub(crate) fn api_error(body: &serde_json::Value) -> Option<String> {
+    match body.get("error")? {
+        serde_json::Value::Null => None,
+        serde_json::Value::String(error) => Some(error.clone()),
+        error => Some(error.to_string()),
     }
 }
@@ -0,0 +1,49 @@
+mod common;
+
+use common::Fake;
+use random_user::UserGenerator;
+use std::sync::atomic::Ordering;
+
+async fn requests_for_two_fetches(fake: Fake) -> usize {
+    let answered = fake.answered();
+    let generator = UserGenerator::new().with_transport(fake).with_cache(16);
+    for _ in 0..2 {
+        let _ = generator.get().seed("foobar").fetch(5).await;
+    }
+    answered.load(Ordering::SeqCst)
+}
+
+#[tokio::test]
+async fn seeded_responses_are_cached() {
+    assert_eq!(
+        requests_for_two_fetches(Fake::new(common::EMPTY_PAGE)).await,
+        1
+    );
+}
+
+#[tokio::test]
+async fn json_errors_are_not_cached() {
+    assert_eq!(
+        requests_for_two_fetches(Fake::new(r#"{"error":"Uh oh"}"#)).await,
+        2
+    );
+}
+
+#[tokio::test]
+async fn plain_text_errors_are_not_cached() {
+    let fake = Fake::new("Uh oh").content_type("text/plain");
+    assert_eq!(requests_for_two_fetches(fake).await, 2);
+}
+
+#[tokio::test]
+async fn huge_caches_are_not_allocated_up_front() {
+    let fake = Fake::new(common::EMPTY_PAGE);
+    let answered = fake.answered();
+    let generator = UserGenerator::new()
+        .with_transport(fake)
+        .with_cache(usize::MAX);
+    for _ in 0..2 {
+        generator.get().seed("foobar").fetch(5).await.unwrap();
+    }
+    assert_eq!(answered.load(Ordering::SeqCst), 1);
+}
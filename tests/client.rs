@@ -0,0 +1,70 @@
+mod common;
+
+use common::Fake;
+use random_user::UserGenerator;
+use reqwest::header::USER_AGENT;
+use reqwest::Request;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// Property of the request sent by `generator`, read by `read`
+async fn sent<T: Send + 'static>(
+    generator: UserGenerator,
+    read: impl Fn(&Request) -> T + Send + Sync + 'static,
+) -> T {
+    let sent = Arc::new(Mutex::new(None));
+    let recorded = sent.clone();
+    let generator = generator.with_transport(Fake::with_hook(move |request| {
+        *recorded.lock().unwrap() = Some(read(request));
+        common::EMPTY_PAGE.to_owned()
+    }));
+    generator.fetch(5).await.unwrap();
+    let sent = sent.lock().unwrap().take();
+    sent.unwrap()
+}
+
+fn timeout(request: &Request) -> Option<Duration> {
+    request.timeout().copied()
+}
+
+#[tokio::test]
+async fn new_generators_time_out_after_30_seconds() {
+    assert_eq!(
+        sent(UserGenerator::new(), timeout).await,
+        Some(Duration::from_secs(30))
+    );
+}
+
+#[tokio::test]
+async fn custom_clients_keep_their_timeout() {
+    let generator = UserGenerator::with_client(reqwest::Client::new());
+    assert_eq!(sent(generator.clone(), timeout).await, None);
+
+    let generator = generator.with_timeout(Duration::from_secs(5));
+    assert_eq!(sent(generator, timeout).await, Some(Duration::from_secs(5)));
+}
+
+fn user_agent(request: &Request) -> Option<String> {
+    request
+        .headers()
+        .get(USER_AGENT)
+        .map(|user_agent| user_agent.to_str().unwrap().to_owned())
+}
+
+#[tokio::test]
+async fn new_generators_send_their_user_agent() {
+    let user_agent = sent(UserGenerator::new(), user_agent).await.unwrap();
+    assert!(user_agent.starts_with("random_user/"));
+}
+
+#[tokio::test]
+async fn custom_clients_keep_their_user_agent() {
+    let generator = UserGenerator::with_client(reqwest::Client::new());
+    assert_eq!(sent(generator.clone(), user_agent).await, None);
+
+    let generator = generator.with_user_agent("my-app/1.0").unwrap();
+    assert_eq!(
+        sent(generator, user_agent).await.as_deref(),
+        Some("my-app/1.0")
+    );
+}
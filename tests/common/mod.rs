@@ -0,0 +1,82 @@
+//! Fake transport shared by the integration tests
+
+#![allow(dead_code)]
+
+use futures_util::future::BoxFuture;
+use random_user::{build_response, json_response, RandomUserError, UserTransport};
+use reqwest::header::{HeaderMap, HeaderValue, CONTENT_TYPE};
+use reqwest::StatusCode;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Page without any user, along with the api informations
+pub const EMPTY_PAGE: &str =
+    r#"{"results":[],"info":{"seed":"foobar","results":0,"page":1,"version":"1.4"}}"#;
+
+type Hook = dyn Fn(&reqwest::Request) -> String + Send + Sync;
+
+/// Transport answering every request with a body, counting the answered requests
+pub struct Fake {
+    hook: Box<Hook>,
+    content_type: Option<&'static str>,
+    delay: Duration,
+    answered: Arc<AtomicUsize>,
+}
+
+impl Fake {
+    /// Answer every request with the same json body
+    pub fn new(body: &'static str) -> Self {
+        Self::with_hook(move |_| body.to_owned())
+    }
+
+    /// Answer each request with the json body returned by `hook`, which can also record it
+    pub fn with_hook(hook: impl Fn(&reqwest::Request) -> String + Send + Sync + 'static) -> Self {
+        Self {
+            hook: Box::new(hook),
+            content_type: None,
+            delay: Duration::ZERO,
+            answered: Arc::new(AtomicUsize::new(0)),
+        }
+    }
+
+    /// Send the body with another content type than json
+    pub fn content_type(self, content_type: &'static str) -> Self {
+        Self {
+            content_type: Some(content_type),
+            ..self
+        }
+    }
+
+    /// Wait before answering, a request cancelled meanwhile isn't counted
+    pub fn delay(self, delay: Duration) -> Self {
+        Self { delay, ..self }
+    }
+
+    /// Counter of the answered requests, still readable once the fake is given to a generator
+    pub fn answered(&self) -> Arc<AtomicUsize> {
+        self.answered.clone()
+    }
+}
+
+impl UserTransport for Fake {
+    fn send(
+        &self,
+        request: reqwest::Request,
+    ) -> BoxFuture<'_, Result<reqwest::Response, RandomUserError>> {
+        let body = (self.hook)(&request);
+        Box::pin(async move {
+            tokio::time::sleep(self.delay).await;
+            self.answered.fetch_add(1, Ordering::SeqCst);
+            let response = match self.content_type {
+                None => json_response(body),
+                Some(content_type) => {
+                    let mut headers = HeaderMap::new();
+                    headers.insert(CONTENT_TYPE, HeaderValue::from_static(content_type));
+                    build_response(StatusCode::OK, headers, body)
+                }
+            };
+            Ok(response)
+        })
+    }
+}
@@ -0,0 +1,84 @@
+use random_user::{
+    Gender, Nationality, RandomUserError, RandomUserInfo, RandomUserResult, UserGenerator,
+};
+
+#[tokio::test]
+async fn filtered_fetch_rejects_huge_counts() {
+    let users = UserGenerator::mock(Vec::new())
+        .get()
+        .filter(|user| user.gender == Gender::Female)
+        .fetch(usize::MAX)
+        .await;
+    assert!(matches!(
+        users,
+        Err(RandomUserError::InvalidCount(usize::MAX))
+    ));
+}
+
+#[tokio::test]
+async fn fetch_many_rejects_huge_counts() {
+    let users = UserGenerator::mock(Vec::new())
+        .get()
+        .fetch_many(usize::MAX, 2)
+        .await;
+    assert!(matches!(
+        users,
+        Err(RandomUserError::InvalidCount(usize::MAX))
+    ));
+}
+
+/// Empty pages for the requests of the mock
+fn empty_pages(count: usize) -> Vec<RandomUserResult> {
+    let page = RandomUserResult {
+        results: Vec::new(),
+        info: RandomUserInfo {
+            seed: "foobar".to_owned(),
+            results: 0,
+            page: 1,
+            version: "1.4".to_owned(),
+        },
+    };
+    vec![page; count]
+}
+
+#[tokio::test]
+async fn age_buckets_top_up_huge_counts() {
+    let users = UserGenerator::mock(empty_pages(10))
+        .get()
+        .fetch_age_buckets(&[(18, 29, usize::MAX), (30, 49, 10)])
+        .await;
+    assert!(matches!(
+        users,
+        Err(RandomUserError::NotEnoughUsers {
+            requested: usize::MAX,
+            found: 0
+        })
+    ));
+}
+
+#[tokio::test]
+async fn age_buckets_request_at_most_max_results() {
+    let users = UserGenerator::mock(empty_pages(10))
+        .get()
+        .fetch_age_buckets(&[(18, 120, 4000), (18, 120, 2000)])
+        .await;
+    assert!(matches!(
+        users,
+        Err(RandomUserError::NotEnoughUsers {
+            requested: 6000,
+            found: 0
+        })
+    ));
+}
+
+#[tokio::test]
+async fn weighted_nationalities_reject_huge_counts() {
+    let users = UserGenerator::mock(Vec::new())
+        .get()
+        .weighted_nationalities(
+            usize::MAX,
+            &[(Nationality::American, 7), (Nationality::French, 3)],
+        )
+        .await;
+    assert!(matches!(users, Err(RandomUserError::InvalidCount(_))));
+}
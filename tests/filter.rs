@@ -0,0 +1,68 @@
+mod common;
+
+use common::Fake;
+use random_user::{RandomUserError, UserGenerator};
+use std::sync::atomic::Ordering;
+
+async fn requests_for_filtered_fetch(seed: Option<&str>) -> usize {
+    let fake = Fake::new(common::EMPTY_PAGE);
+    let answered = fake.answered();
+    let generator = UserGenerator::new().with_transport(fake);
+    let mut builder = generator.get().filter(|_| true);
+    if let Some(seed) = seed {
+        builder = builder.seed(seed);
+    }
+    let users = builder.fetch(5).await;
+    assert!(matches!(
+        users,
+        Err(RandomUserError::NotEnoughUsers {
+            requested: 5,
+            found: 0
+        })
+    ));
+    answered.load(Ordering::SeqCst)
+}
+
+#[tokio::test]
+async fn filtered_fetch_tops_up_random_users() {
+    assert_eq!(requests_for_filtered_fetch(None).await, 10);
+}
+
+#[tokio::test]
+async fn filtered_fetch_sends_a_single_seeded_request() {
+    assert_eq!(requests_for_filtered_fetch(Some("foobar")).await, 1);
+}
+
+#[tokio::test]
+async fn filtered_fetch_one_with_seed_tries_another_user() {
+    let fake = Fake::new(common::EMPTY_PAGE);
+    let answered = fake.answered();
+    let user = UserGenerator::new()
+        .with_transport(fake)
+        .get()
+        .filter(|_| true)
+        .fetch_one_with_seed()
+        .await;
+    assert!(matches!(
+        user,
+        Err(RandomUserError::NotEnoughUsers {
+            requested: 1,
+            found: 0
+        })
+    ));
+    assert_eq!(answered.load(Ordering::SeqCst), 10);
+}
+
+#[tokio::test]
+async fn seeded_age_buckets_send_a_single_request() {
+    let fake = Fake::new(common::EMPTY_PAGE);
+    let answered = fake.answered();
+    let users = UserGenerator::new()
+        .with_transport(fake)
+        .get()
+        .seed("foobar")
+        .fetch_age_buckets(&[(18, 29, 5)])
+        .await;
+    assert!(matches!(users, Err(RandomUserError::NotEnoughUsers { .. })));
+    assert_eq!(answered.load(Ordering::SeqCst), 1);
+}
@@ -0,0 +1,21 @@
+mod common;
+
+use common::Fake;
+use random_user::UserGenerator;
+use std::sync::atomic::Ordering;
+use std::time::{Duration, Instant};
+
+#[tokio::test]
+async fn lenient_fetches_send_concurrent_requests() {
+    let fake = Fake::new(common::EMPTY_PAGE).delay(Duration::from_millis(200));
+    let answered = fake.answered();
+    let generator = UserGenerator::new().with_transport(fake);
+
+    let start = Instant::now();
+    let (users, errors) = generator.get().fetch_lenient(20000, 4).await;
+    assert!(users.is_empty());
+    assert!(errors.is_empty());
+    assert_eq!(answered.load(Ordering::SeqCst), 4);
+    // One request at a time would take 800ms
+    assert!(start.elapsed() < Duration::from_millis(600));
+}
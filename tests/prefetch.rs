@@ -0,0 +1,58 @@
+mod common;
+
+use common::Fake;
+use futures_util::StreamExt;
+use random_user::UserGenerator;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+const USER: &str = include_str!("user.json");
+
+/// Serve a dataset of `pages` pages, the last one holding a single user,
+/// counting the requests answered
+fn dataset(pages: usize, delay: Duration) -> (UserGenerator, Arc<AtomicUsize>) {
+    let fake = Fake::with_hook(move |request| {
+        let query = |key: &str| {
+            request
+                .url()
+                .query_pairs()
+                .find(|(k, _)| k == key)
+                .and_then(|(_, value)| value.parse().ok())
+                .unwrap_or(1)
+        };
+        let page = query("page");
+        let count = match page {
+            page if page < pages => query("results"),
+            page if page == pages => 1,
+            _ => 0,
+        };
+        let users = vec![USER; count].join(",");
+        format!(
+            r#"{{"results":[{users}],"info":{{"seed":"foobar","results":{count},"page":{page},"version":"1.4"}}}}"#
+        )
+    })
+    .delay(delay);
+    let answered = fake.answered();
+    (UserGenerator::new().with_transport(fake), answered)
+}
+
+#[tokio::test]
+async fn prefetched_stops_after_the_short_page() {
+    let (generator, answered) = dataset(2, Duration::ZERO);
+
+    let users: Vec<_> = generator.iter("foobar", 2).collect().await;
+    assert_eq!(users.len(), 3);
+    assert!(users.iter().all(Result::is_ok));
+    assert_eq!(answered.load(Ordering::SeqCst), 2);
+}
+
+#[tokio::test]
+async fn dropping_prefetched_cancels_the_next_page() {
+    let (generator, answered) = dataset(5, Duration::from_millis(50));
+
+    let users = generator.iter("foobar", 2).take(1);
+    assert_eq!(users.collect::<Vec<_>>().await.len(), 1);
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    assert_eq!(answered.load(Ordering::SeqCst), 1);
+}
@@ -0,0 +1,67 @@
+mod common;
+
+use common::Fake;
+use random_user::UserGenerator;
+use std::sync::{Arc, Mutex};
+
+fn query(url: &url::Url, key: &str) -> Option<String> {
+    url.query_pairs()
+        .find(|(k, _)| k == key)
+        .map(|(_, value)| value.into_owned())
+}
+
+#[tokio::test]
+async fn same_seed_page_and_count_send_the_same_request() {
+    let urls = Arc::new(Mutex::new(Vec::new()));
+    let recorded = urls.clone();
+    let generator = UserGenerator::new().with_transport(Fake::with_hook(move |request| {
+        recorded.lock().unwrap().push(request.url().clone());
+        common::EMPTY_PAGE.to_owned()
+    }));
+
+    let first = generator
+        .get()
+        .seed("foobar")
+        .page(2)
+        .fetch_with_info(5)
+        .await
+        .unwrap();
+    let second = generator
+        .get()
+        .seed("foobar")
+        .page(2)
+        .fetch_with_info(5)
+        .await
+        .unwrap();
+    assert_eq!(first, second);
+
+    let urls = urls.lock().unwrap();
+    assert_eq!(urls.len(), 2);
+    assert_eq!(urls[0], urls[1]);
+    assert_eq!(query(&urls[0], "seed").as_deref(), Some("foobar"));
+    assert_eq!(query(&urls[0], "page").as_deref(), Some("2"));
+    assert_eq!(query(&urls[0], "results").as_deref(), Some("5"));
+}
+
+#[tokio::test]
+#[ignore = "needs the randomuser.me api"]
+async fn same_seed_page_and_count_return_the_same_users() {
+    let generator = UserGenerator::new();
+
+    let first = generator
+        .get()
+        .seed("foobar")
+        .page(3)
+        .fetch(10)
+        .await
+        .unwrap();
+    let second = generator
+        .get()
+        .seed("foobar")
+        .page(3)
+        .fetch(10)
+        .await
+        .unwrap();
+    assert_eq!(first.len(), 10);
+    assert_eq!(first, second);
+}